@@ -7,7 +7,7 @@ fn main() -> recibo::Result<()> {
   printer
     .init()?
     .align(Alignment::Center)?
-    .qr(|builder| builder.size(200).text("Hello World"))?
+    .qr(|builder| builder.size(8).text("Hello World"))?
     .text("Hello World")?
     .feed(4)?
     .cut()?
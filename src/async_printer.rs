@@ -0,0 +1,162 @@
+use std::sync::{Arc, Mutex};
+
+use log::debug;
+
+#[cfg(feature = "fonts")]
+use crate::domain::TextImageBuilder;
+use crate::domain::{
+  Alignment, AsbReport, AztecBuilder, BarcodeBuilder, DataMatrixBuilder, Font, Hardware,
+  Pdf417Builder, QrBuilder, Status, StatusKind, UnderlineMode,
+};
+#[cfg(feature = "graphics")]
+use crate::domain::{Graphic, GraphicBuilder};
+use crate::error::{PrinterError, Result};
+use crate::io::{AsyncDriver, Driver};
+use crate::printer::{Printer, PrinterBuilder, REPLY_TIMEOUT};
+
+/// Generates the commands of an async printer, which collect the bytes written by the same
+/// command of the wrapped `Printer`
+macro_rules! commands {
+  ($(
+    $(#[$meta:meta])*
+    fn $name:ident$(<$generic:ident: $bound:path>)?($($arg:ident: $ty:ty),*);
+  )*) => {
+    $(
+      $(#[$meta])*
+      pub fn $name$(<$generic: $bound>)?(&mut self, $($arg: $ty),*) -> Result<&mut Self> {
+        self.printer.$name($($arg),*)?;
+        Ok(self)
+      }
+    )*
+  };
+}
+
+/// A printer for async runtimes. It has the same commands as `Printer`, which collect their
+/// bytes until `flush` sends them through an `AsyncDriver`.
+pub struct AsyncPrinter {
+  driver: Box<dyn AsyncDriver>,
+  buffer: BufferDriver,
+  printer: Printer,
+}
+
+impl AsyncPrinter {
+  pub fn open(driver: Box<dyn AsyncDriver>) -> Result<Self> {
+    Ok(Printer::builder().build_async(driver))
+  }
+
+  pub(crate) fn new(driver: Box<dyn AsyncDriver>, builder: PrinterBuilder) -> Self {
+    debug!("Creating async printer with driver: {}", driver.name());
+    let buffer = BufferDriver::default();
+    let printer = builder.driver(Box::new(buffer.clone())).build();
+    Self {
+      driver,
+      buffer,
+      printer,
+    }
+  }
+
+  commands! {
+    fn hardware(hardware: Hardware);
+    fn font(font: Font);
+    fn align(alignment: Alignment);
+    fn doublestrike(enabled: bool);
+    fn linespacing(height: u8);
+    fn flip(enabled: bool);
+    fn reset_linespacing();
+    fn bold(enabled: bool);
+    fn underline(mode: UnderlineMode);
+    fn reset();
+    fn init();
+    fn feed(n: u8);
+    fn reverse_feed(n: u8);
+    fn cut();
+    fn partial_cut();
+    fn print<T: AsRef<str>>(text: T);
+    fn println<T: AsRef<str>>(text: T);
+    fn text<T: AsRef<str>>(text: T);
+    fn text_size(width_multiplier: u8, height_multiplier: u8);
+    fn reset_text_size();
+    fn barcode<F: Fn(&mut BarcodeBuilder) -> &mut BarcodeBuilder>(function: F);
+    fn qr<F: Fn(&mut QrBuilder) -> &mut QrBuilder>(function: F);
+    fn pdf417<F: Fn(&mut Pdf417Builder) -> &mut Pdf417Builder>(function: F);
+    fn data_matrix<F: Fn(&mut DataMatrixBuilder) -> &mut DataMatrixBuilder>(function: F);
+    fn aztec<F: Fn(&mut AztecBuilder) -> &mut AztecBuilder>(function: F);
+    #[cfg(feature = "graphics")]
+    fn graphic<F: Fn(&mut GraphicBuilder) -> &mut GraphicBuilder>(function: F);
+    #[cfg(feature = "fonts")]
+    fn text_image<F: Fn(&mut TextImageBuilder) -> &mut TextImageBuilder>(function: F);
+    #[cfg(feature = "fonts")]
+    fn complex_text<F: Fn(&mut TextImageBuilder) -> &mut TextImageBuilder>(function: F);
+    #[cfg(feature = "fonts")]
+    fn reset_complex_text();
+    #[cfg(feature = "graphics")]
+    fn define_nv_graphic<F: Fn(&mut GraphicBuilder) -> &mut GraphicBuilder>(
+      key: &str,
+      function: F
+    );
+    fn nv_graphic(key: &str);
+    fn delete_nv_graphic(key: &str);
+    fn delete_nv_graphics();
+    #[cfg(feature = "graphics")]
+    fn define_nv_bit_images(graphics: Vec<Graphic>);
+    fn nv_bit_image(number: u8);
+    #[cfg(feature = "graphics")]
+    fn define_download_bit_image<F: Fn(&mut GraphicBuilder) -> &mut GraphicBuilder>(
+      function: F
+    );
+    fn download_bit_image();
+    fn reverse_colours(enabled: bool);
+    fn left(dots: u16);
+    fn width(margin: u16);
+    fn write(bytes: &[u8]);
+    fn asb(reports: &[AsbReport]);
+  }
+
+  /// Sends the commands collected since the last flush to the printer
+  pub async fn flush(&mut self) -> Result<()> {
+    let bytes = self.buffer.take()?;
+    self.driver.write(&bytes).await?;
+    self.driver.flush().await
+  }
+
+  /// Sends the commands collected since the last flush with a status request, and waits for
+  /// the reply. Only drivers that can read from the printer are supported.
+  pub async fn status(&mut self, kind: StatusKind) -> Result<Status> {
+    self.printer.request_status(&kind)?;
+    self.flush().await?;
+
+    let mut reply = [0; 1];
+    if self.driver.read(&mut reply, REPLY_TIMEOUT).await? == 0 {
+      return Err(PrinterError::Io(format!(
+        "No reply to the {} status request",
+        kind
+      )));
+    }
+    kind.parse(reply[0])
+  }
+}
+
+/// Collects the commands of an async printer until they are flushed
+#[derive(Clone, Default)]
+struct BufferDriver(Arc<Mutex<Vec<u8>>>);
+
+impl BufferDriver {
+  fn take(&self) -> Result<Vec<u8>> {
+    Ok(std::mem::take(&mut *self.0.lock()?))
+  }
+}
+
+impl Driver for BufferDriver {
+  fn name(&self) -> &'static str {
+    "buffer"
+  }
+
+  fn write(&self, data: &[u8]) -> Result<()> {
+    self.0.lock()?.extend_from_slice(data);
+    Ok(())
+  }
+
+  fn flush(&self) -> Result<()> {
+    Ok(())
+  }
+}
@@ -0,0 +1,315 @@
+use std::fmt;
+
+use crate::error::{PrinterError, Result};
+
+/// A status the printer reports automatically with Automatic Status Back (ASB) when it changes
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum AsbReport {
+  /// The drawer kick-out connector
+  #[cfg_attr(feature = "serde", serde(rename = "drawer"))]
+  Drawer,
+  #[cfg_attr(feature = "serde", serde(rename = "online"))]
+  Online,
+  #[cfg_attr(feature = "serde", serde(rename = "error"))]
+  Error,
+  /// The roll paper sensors
+  #[cfg_attr(feature = "serde", serde(rename = "paper"))]
+  Paper,
+}
+
+impl fmt::Display for AsbReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AsbReport::Drawer => write!(f, "drawer"),
+      AsbReport::Online => write!(f, "online"),
+      AsbReport::Error => write!(f, "error"),
+      AsbReport::Paper => write!(f, "paper"),
+    }
+  }
+}
+
+impl From<&AsbReport> for u8 {
+  fn from(report: &AsbReport) -> Self {
+    match report {
+      AsbReport::Drawer => 0x01,
+      AsbReport::Online => 0x02,
+      AsbReport::Error => 0x04,
+      AsbReport::Paper => 0x08,
+    }
+  }
+}
+
+/// The printer status sent in a 4-byte ASB message
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AsbStatus {
+  drawer_pin_high: bool,
+  offline: bool,
+  cover_open: bool,
+  recoverable_error: bool,
+  cutter_error: bool,
+  unrecoverable_error: bool,
+  auto_recoverable_error: bool,
+  paper_near_end: bool,
+  paper_end: bool,
+}
+
+impl AsbStatus {
+  /// Returns true if the byte can start an ASB message, which has bit 4 set and bits 0, 1
+  /// and 7 clear. Real-time status replies have bit 1 set instead.
+  pub(crate) fn is_header(byte: u8) -> bool {
+    byte & 0x93 == 0x10
+  }
+
+  /// Returns true if the bytes are an ASB message, where the bytes after the header have bits
+  /// 4 and 7 clear
+  pub(crate) fn is_message(message: &[u8]) -> bool {
+    match message {
+      [first, rest @ ..] if rest.len() == 3 => {
+        Self::is_header(*first) && rest.iter().all(|byte| byte & 0x90 == 0)
+      }
+      _ => false,
+    }
+  }
+
+  pub fn parse(message: [u8; 4]) -> Result<Self> {
+    if !Self::is_message(&message) {
+      return Err(PrinterError::Io(format!(
+        "Unexpected ASB message: {:02x?}",
+        message
+      )));
+    }
+    let bit = |byte: usize, n: u8| message[byte] & (1 << n) != 0;
+
+    // the fourth byte is reserved
+    Ok(Self {
+      drawer_pin_high: bit(0, 2),
+      offline: bit(0, 3),
+      cover_open: bit(0, 5),
+      recoverable_error: bit(1, 2),
+      cutter_error: bit(1, 3),
+      unrecoverable_error: bit(1, 5),
+      auto_recoverable_error: bit(1, 6),
+      paper_near_end: message[2] & 0x03 != 0,
+      paper_end: message[2] & 0x0C != 0,
+    })
+  }
+
+  /// Whether pin 3 of the drawer kick-out connector is high. Which level means the drawer is
+  /// open depends on the drawer.
+  pub fn drawer_pin_high(&self) -> bool {
+    self.drawer_pin_high
+  }
+
+  pub fn offline(&self) -> bool {
+    self.offline
+  }
+
+  pub fn cover_open(&self) -> bool {
+    self.cover_open
+  }
+
+  /// Whether any error has occurred
+  pub fn error(&self) -> bool {
+    self.recoverable_error
+      || self.cutter_error
+      || self.unrecoverable_error
+      || self.auto_recoverable_error
+  }
+
+  /// A mechanical error, such as a paper jam, that is recovered from once the cause is removed
+  pub fn recoverable_error(&self) -> bool {
+    self.recoverable_error
+  }
+
+  pub fn cutter_error(&self) -> bool {
+    self.cutter_error
+  }
+
+  pub fn unrecoverable_error(&self) -> bool {
+    self.unrecoverable_error
+  }
+
+  pub fn auto_recoverable_error(&self) -> bool {
+    self.auto_recoverable_error
+  }
+
+  pub fn paper_near_end(&self) -> bool {
+    self.paper_near_end
+  }
+
+  pub fn paper_end(&self) -> bool {
+    self.paper_end
+  }
+
+  /// The events between a previous status and this one
+  pub fn events(&self, previous: &AsbStatus) -> Vec<PrinterEvent> {
+    let changes = [
+      (
+        previous.paper_near_end,
+        self.paper_near_end,
+        Some(PrinterEvent::PaperNearEnd),
+        None,
+      ),
+      (
+        previous.paper_end,
+        self.paper_end,
+        Some(PrinterEvent::PaperEnd),
+        Some(PrinterEvent::PaperLoaded),
+      ),
+      (
+        previous.cover_open,
+        self.cover_open,
+        Some(PrinterEvent::CoverOpened),
+        Some(PrinterEvent::CoverClosed),
+      ),
+      (
+        previous.drawer_pin_high,
+        self.drawer_pin_high,
+        Some(PrinterEvent::DrawerOpened),
+        Some(PrinterEvent::DrawerClosed),
+      ),
+      (
+        previous.offline,
+        self.offline,
+        Some(PrinterEvent::Offline),
+        Some(PrinterEvent::Online),
+      ),
+      (
+        previous.error(),
+        self.error(),
+        Some(PrinterEvent::Error),
+        Some(PrinterEvent::ErrorRecovered),
+      ),
+    ];
+
+    changes
+      .into_iter()
+      .filter_map(|(before, after, set, cleared)| match (before, after) {
+        (false, true) => set,
+        (true, false) => cleared,
+        _ => None,
+      })
+      .collect()
+  }
+}
+
+/// A change in the printer status reported with ASB
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum PrinterEvent {
+  #[cfg_attr(feature = "serde", serde(rename = "paper_near_end"))]
+  PaperNearEnd,
+  #[cfg_attr(feature = "serde", serde(rename = "paper_end"))]
+  PaperEnd,
+  #[cfg_attr(feature = "serde", serde(rename = "paper_loaded"))]
+  PaperLoaded,
+  #[cfg_attr(feature = "serde", serde(rename = "cover_opened"))]
+  CoverOpened,
+  #[cfg_attr(feature = "serde", serde(rename = "cover_closed"))]
+  CoverClosed,
+  /// Pin 3 of the drawer kick-out connector went high. Some drawers report the opposite level.
+  #[cfg_attr(feature = "serde", serde(rename = "drawer_opened"))]
+  DrawerOpened,
+  #[cfg_attr(feature = "serde", serde(rename = "drawer_closed"))]
+  DrawerClosed,
+  #[cfg_attr(feature = "serde", serde(rename = "offline"))]
+  Offline,
+  #[cfg_attr(feature = "serde", serde(rename = "online"))]
+  Online,
+  #[cfg_attr(feature = "serde", serde(rename = "error"))]
+  Error,
+  #[cfg_attr(feature = "serde", serde(rename = "error_recovered"))]
+  ErrorRecovered,
+}
+
+impl fmt::Display for PrinterEvent {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PrinterEvent::PaperNearEnd => write!(f, "paper_near_end"),
+      PrinterEvent::PaperEnd => write!(f, "paper_end"),
+      PrinterEvent::PaperLoaded => write!(f, "paper_loaded"),
+      PrinterEvent::CoverOpened => write!(f, "cover_opened"),
+      PrinterEvent::CoverClosed => write!(f, "cover_closed"),
+      PrinterEvent::DrawerOpened => write!(f, "drawer_opened"),
+      PrinterEvent::DrawerClosed => write!(f, "drawer_closed"),
+      PrinterEvent::Offline => write!(f, "offline"),
+      PrinterEvent::Online => write!(f, "online"),
+      PrinterEvent::Error => write!(f, "error"),
+      PrinterEvent::ErrorRecovered => write!(f, "error_recovered"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse() -> Result<()> {
+    let status = AsbStatus::parse([0x10, 0x00, 0x00, 0x00])?;
+    assert_eq!(status, AsbStatus::default());
+
+    // offline with the cover open and the paper out
+    let status = AsbStatus::parse([0x38, 0x00, 0x0C, 0x00])?;
+    assert!(status.offline() && status.cover_open() && status.paper_end());
+    assert!(!status.paper_near_end() && !status.error() && !status.drawer_pin_high());
+
+    let status = AsbStatus::parse([0x14, 0x00, 0x03, 0x00])?;
+    assert!(status.drawer_pin_high() && status.paper_near_end());
+    assert!(!status.paper_end());
+
+    let error = |byte: u8| AsbStatus::parse([0x10, byte, 0x00, 0x00]);
+    assert!(error(0x04)?.recoverable_error());
+    assert!(error(0x08)?.cutter_error());
+    assert!(error(0x20)?.unrecoverable_error());
+    assert!(error(0x40)?.auto_recoverable_error());
+    assert!([0x04, 0x08, 0x20, 0x40]
+      .iter()
+      .all(|byte| error(*byte).map_or(false, |status| status.error())));
+
+    // a real-time status reply, and bytes with bit 4 set after the header
+    assert!(AsbStatus::parse([0x12, 0x00, 0x00, 0x00]).is_err());
+    assert!(AsbStatus::parse([0x10, 0x10, 0x00, 0x00]).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_events() -> Result<()> {
+    let ready = AsbStatus::default();
+    let near_end = AsbStatus::parse([0x10, 0x00, 0x03, 0x00])?;
+    assert_eq!(near_end.events(&ready), vec![PrinterEvent::PaperNearEnd]);
+    assert_eq!(near_end.events(&near_end), vec![]);
+    assert_eq!(ready.events(&near_end), vec![]);
+
+    let out = AsbStatus::parse([0x10, 0x00, 0x0F, 0x00])?;
+    assert_eq!(out.events(&near_end), vec![PrinterEvent::PaperEnd]);
+    assert_eq!(ready.events(&out), vec![PrinterEvent::PaperLoaded]);
+
+    let open = AsbStatus::parse([0x34, 0x00, 0x00, 0x00])?;
+    assert_eq!(
+      open.events(&ready),
+      vec![PrinterEvent::CoverOpened, PrinterEvent::DrawerOpened]
+    );
+
+    let error = AsbStatus::parse([0x18, 0x40, 0x00, 0x00])?;
+    assert_eq!(
+      error.events(&ready),
+      vec![PrinterEvent::Offline, PrinterEvent::Error]
+    );
+    assert_eq!(
+      ready.events(&error),
+      vec![PrinterEvent::Online, PrinterEvent::ErrorRecovered]
+    );
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_from_json() {
+    let report: super::AsbReport = serde_json::from_str("\"paper\"").unwrap();
+    assert_eq!(report, super::AsbReport::Paper);
+  }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum AztecMode {
+  #[cfg_attr(feature = "serde", serde(rename = "full_range"))]
+  FullRange,
+  #[cfg_attr(feature = "serde", serde(rename = "compact"))]
+  Compact,
+}
+
+impl fmt::Display for AztecMode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AztecMode::FullRange => write!(f, "full_range"),
+      AztecMode::Compact => write!(f, "compact"),
+    }
+  }
+}
+
+impl From<&AztecMode> for u8 {
+  fn from(mode: &AztecMode) -> Self {
+    match mode {
+      AztecMode::FullRange => 0x00,
+      AztecMode::Compact => 0x01,
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct Aztec {
+  mode: AztecMode,
+  layers: u8,
+  correction_level: u8,
+  size: u8,
+  text: String,
+}
+
+impl fmt::Display for Aztec {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "mode: {}, layers: {}, correction_level: {}, size: {}, text: {}",
+      self.mode, self.layers, self.correction_level, self.size, self.text
+    )
+  }
+}
+
+impl Aztec {
+  pub fn new(mode: AztecMode, layers: u8, correction_level: u8, size: u8, text: String) -> Self {
+    Self {
+      mode,
+      layers,
+      correction_level,
+      size,
+      text,
+    }
+  }
+
+  pub fn mode(&self) -> &AztecMode {
+    &self.mode
+  }
+
+  /// The number of data layers, 0 lets the printer decide
+  pub fn layers(&self) -> u8 {
+    self.layers
+  }
+
+  /// The percentage of the symbol used for error correction
+  pub fn correction_level(&self) -> u8 {
+    self.correction_level
+  }
+
+  /// The module size in dots
+  pub fn size(&self) -> u8 {
+    self.size
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn builder() -> AztecBuilder {
+    AztecBuilder::default()
+  }
+}
+
+impl Default for Aztec {
+  fn default() -> Self {
+    Self::new(AztecMode::FullRange, 0, 23, 3, String::new())
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AztecBuilder(Aztec);
+
+impl AztecBuilder {
+  pub fn mode(&mut self, mode: AztecMode) -> &mut Self {
+    self.0.mode = mode;
+    self
+  }
+
+  pub fn layers(&mut self, layers: u8) -> &mut Self {
+    self.0.layers = layers;
+    self
+  }
+
+  pub fn correction_level(&mut self, correction_level: u8) -> &mut Self {
+    self.0.correction_level = correction_level;
+    self
+  }
+
+  pub fn size(&mut self, size: u8) -> &mut Self {
+    self.0.size = size;
+    self
+  }
+
+  pub fn text<T: AsRef<str>>(&mut self, text: T) -> &mut Self {
+    self.0.text = text.as_ref().to_string();
+    self
+  }
+
+  pub fn build(self) -> Aztec {
+    self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_defaults() {
+    let aztec = Aztec::builder().build();
+    // a full range symbol with the layers picked by the printer
+    assert_eq!(aztec.mode(), &AztecMode::FullRange);
+    assert_eq!(aztec.layers(), 0);
+    assert_eq!(aztec.correction_level(), 23);
+    assert_eq!(aztec.size(), 3);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_from_json() {
+    let mode: AztecMode = serde_json::from_str("\"full_range\"").unwrap();
+    assert_eq!(mode, AztecMode::FullRange);
+    assert!(serde_json::from_str::<AztecMode>("\"FullRange\"").is_err());
+  }
+}
@@ -24,12 +24,47 @@ pub enum BarcodeSystem {
   #[cfg_attr(feature = "serde", serde(rename = "code39"))]
   Code39,
 
-  /// Interleaved 2 of 5 (ITF)
+  /// Interleaved 2 of 5 (ITF), the text is an even number of digits including
+  /// any check digit
   #[cfg_attr(feature = "serde", serde(rename = "itf"))]
   Itf,
 
   #[cfg_attr(feature = "serde", serde(rename = "codebar"))]
   Codabar,
+
+  /// Code 128, the code set is selected automatically unless the text starts
+  /// with one of the `{A`, `{B` or `{C` prefixes
+  #[cfg_attr(feature = "serde", serde(rename = "code128"))]
+  Code128,
+
+  /// GS1-128, the text is a list of application identifiers and their values,
+  /// e.g. `(01)09501101020917(17)190508`
+  #[cfg_attr(feature = "serde", serde(rename = "gs1_128"))]
+  Gs1_128,
+
+  /// Code 93, supports the full ASCII character set
+  #[cfg_attr(feature = "serde", serde(rename = "code93"))]
+  Code93,
+
+  /// GS1 DataBar Omnidirectional, the text is a 13 digit GTIN.
+  ///
+  /// The DataBar systems are printed as linear symbols with `GS k`. The stacked variants,
+  /// which need the `GS ( k` 2D commands, are not supported.
+  #[cfg_attr(feature = "serde", serde(rename = "databar_omnidirectional"))]
+  DataBarOmnidirectional,
+
+  /// GS1 DataBar Truncated, the text is a 13 digit GTIN
+  #[cfg_attr(feature = "serde", serde(rename = "databar_truncated"))]
+  DataBarTruncated,
+
+  /// GS1 DataBar Limited, the text is a 13 digit GTIN starting with 0 or 1
+  #[cfg_attr(feature = "serde", serde(rename = "databar_limited"))]
+  DataBarLimited,
+
+  /// GS1 DataBar Expanded, the text is a list of application identifiers and
+  /// their values, e.g. `(01)09501101020917(3103)000250`
+  #[cfg_attr(feature = "serde", serde(rename = "databar_expanded"))]
+  DataBarExpanded,
 }
 
 impl fmt::Display for BarcodeSystem {
@@ -42,6 +77,13 @@ impl fmt::Display for BarcodeSystem {
       BarcodeSystem::Code39 => write!(f, "CODE39"),
       BarcodeSystem::Itf => write!(f, "ITF"),
       BarcodeSystem::Codabar => write!(f, "CODABAR"),
+      BarcodeSystem::Code128 => write!(f, "CODE128"),
+      BarcodeSystem::Gs1_128 => write!(f, "GS1-128"),
+      BarcodeSystem::Code93 => write!(f, "CODE93"),
+      BarcodeSystem::DataBarOmnidirectional => write!(f, "GS1 DATABAR OMNIDIRECTIONAL"),
+      BarcodeSystem::DataBarTruncated => write!(f, "GS1 DATABAR TRUNCATED"),
+      BarcodeSystem::DataBarLimited => write!(f, "GS1 DATABAR LIMITED"),
+      BarcodeSystem::DataBarExpanded => write!(f, "GS1 DATABAR EXPANDED"),
     }
   }
 }
@@ -109,6 +151,8 @@ pub struct Barcode {
   width: u8,
   height: u8,
   text: String,
+  #[cfg_attr(feature = "serde", serde(default))]
+  raster: bool,
 }
 
 impl fmt::Display for Barcode {
@@ -137,6 +181,7 @@ impl Barcode {
       width,
       height,
       text,
+      raster: false,
     }
   }
 
@@ -164,6 +209,12 @@ impl Barcode {
     &self.font
   }
 
+  /// Whether the barcode is generated by the library and printed as a graphic,
+  /// for symbologies the printer does not support
+  pub fn raster(&self) -> bool {
+    self.raster
+  }
+
   pub fn builder() -> BarcodeBuilder {
     BarcodeBuilder::default()
   }
@@ -173,8 +224,8 @@ impl Default for Barcode {
   fn default() -> Self {
     Self::new(
       BarcodeSystem::UpcA,
-      3,
-      8,
+      2,
+      104,
       String::new(),
       BarcodeTextPosition::Below,
       BarcodeFont::A,
@@ -191,12 +242,12 @@ impl BarcodeBuilder {
     self
   }
 
-  pub fn width(mut self, width: u8) -> Self {
+  pub fn width(&mut self, width: u8) -> &mut Self {
     self.0.width = width;
     self
   }
 
-  pub fn height(mut self, height: u8) -> Self {
+  pub fn height(&mut self, height: u8) -> &mut Self {
     self.0.height = height;
     self
   }
@@ -216,6 +267,13 @@ impl BarcodeBuilder {
     self
   }
 
+  /// Generates the bars in the library and prints them as a graphic. The width is
+  /// used as the number of dots per module. Requires the `graphics` feature.
+  pub fn raster(&mut self, raster: bool) -> &mut Self {
+    self.0.raster = raster;
+    self
+  }
+
   pub fn build(self) -> Barcode {
     self.0
   }
@@ -246,5 +304,6 @@ mod tests {
     assert_eq!(barcode.text(), "123456789012");
     assert_eq!(barcode.text_position(), &super::BarcodeTextPosition::Below);
     assert_eq!(barcode.font(), &super::BarcodeFont::A);
+    assert!(!barcode.raster());
   }
 }
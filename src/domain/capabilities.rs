@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::domain::GraphicMode;
+
+/// Describes what the connected printer can handle, so commands can be adapted to it
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct Capabilities {
+  #[cfg_attr(
+    feature = "serde",
+    serde(default = "Capabilities::default_raster_band_height")
+  )]
+  raster_band_height: u16,
+  #[cfg_attr(feature = "serde", serde(default))]
+  graphic_mode: GraphicMode,
+}
+
+impl fmt::Display for Capabilities {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "raster_band_height: {}, graphic_mode: {}",
+      self.raster_band_height, self.graphic_mode
+    )
+  }
+}
+
+impl Capabilities {
+  pub fn new(raster_band_height: u16, graphic_mode: GraphicMode) -> Self {
+    Self {
+      raster_band_height,
+      graphic_mode,
+    }
+  }
+
+  /// The number of dot rows sent in each raster graphic command. Taller graphics are split into
+  /// several commands so they fit in the receive buffer. 0 sends each graphic in one command.
+  pub fn raster_band_height(&self) -> u16 {
+    self.raster_band_height
+  }
+
+  /// The command used to print graphics
+  pub fn graphic_mode(&self) -> &GraphicMode {
+    &self.graphic_mode
+  }
+
+  pub fn builder() -> CapabilitiesBuilder {
+    CapabilitiesBuilder::default()
+  }
+
+  #[cfg(feature = "serde")]
+  fn default_raster_band_height() -> u16 {
+    Self::default().raster_band_height
+  }
+}
+
+impl Default for Capabilities {
+  fn default() -> Self {
+    Self::new(256, GraphicMode::default())
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CapabilitiesBuilder(Capabilities);
+
+impl CapabilitiesBuilder {
+  pub fn raster_band_height(&mut self, raster_band_height: u16) -> &mut Self {
+    self.0.raster_band_height = raster_band_height;
+    self
+  }
+
+  pub fn graphic_mode(&mut self, graphic_mode: GraphicMode) -> &mut Self {
+    self.0.graphic_mode = graphic_mode;
+    self
+  }
+
+  pub fn build(self) -> Capabilities {
+    self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_from_json() {
+    let capabilities: super::Capabilities =
+      serde_json::from_str(r#"{ "raster_band_height": 24, "graphic_mode": "column24" }"#).unwrap();
+    assert_eq!(capabilities.raster_band_height(), 24);
+    assert_eq!(capabilities.graphic_mode(), &super::GraphicMode::Column24);
+
+    let capabilities: super::Capabilities = serde_json::from_str("{}").unwrap();
+    assert_eq!(capabilities, super::Capabilities::default());
+  }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum DataMatrixSymbol {
+  #[cfg_attr(feature = "serde", serde(rename = "square"))]
+  Square,
+  #[cfg_attr(feature = "serde", serde(rename = "rectangle"))]
+  Rectangle,
+}
+
+impl fmt::Display for DataMatrixSymbol {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      DataMatrixSymbol::Square => write!(f, "square"),
+      DataMatrixSymbol::Rectangle => write!(f, "rectangle"),
+    }
+  }
+}
+
+impl From<&DataMatrixSymbol> for u8 {
+  fn from(symbol: &DataMatrixSymbol) -> Self {
+    match symbol {
+      DataMatrixSymbol::Square => 0x00,
+      DataMatrixSymbol::Rectangle => 0x01,
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct DataMatrix {
+  symbol: DataMatrixSymbol,
+  rows: u8,
+  columns: u8,
+  size: u8,
+  text: String,
+}
+
+impl fmt::Display for DataMatrix {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "symbol: {}, rows: {}, columns: {}, size: {}, text: {}",
+      self.symbol, self.rows, self.columns, self.size, self.text
+    )
+  }
+}
+
+impl DataMatrix {
+  pub fn new(symbol: DataMatrixSymbol, rows: u8, columns: u8, size: u8, text: String) -> Self {
+    Self {
+      symbol,
+      rows,
+      columns,
+      size,
+      text,
+    }
+  }
+
+  pub fn symbol(&self) -> &DataMatrixSymbol {
+    &self.symbol
+  }
+
+  /// The number of rows, 0 lets the printer decide
+  pub fn rows(&self) -> u8 {
+    self.rows
+  }
+
+  /// The number of columns, 0 lets the printer decide. Square symbols only use the rows.
+  pub fn columns(&self) -> u8 {
+    self.columns
+  }
+
+  /// The module size in dots
+  pub fn size(&self) -> u8 {
+    self.size
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn builder() -> DataMatrixBuilder {
+    DataMatrixBuilder::default()
+  }
+}
+
+impl Default for DataMatrix {
+  fn default() -> Self {
+    Self::new(DataMatrixSymbol::Square, 0, 0, 3, String::new())
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DataMatrixBuilder(DataMatrix);
+
+impl DataMatrixBuilder {
+  pub fn symbol(&mut self, symbol: DataMatrixSymbol) -> &mut Self {
+    self.0.symbol = symbol;
+    self
+  }
+
+  pub fn rows(&mut self, rows: u8) -> &mut Self {
+    self.0.rows = rows;
+    self
+  }
+
+  pub fn columns(&mut self, columns: u8) -> &mut Self {
+    self.0.columns = columns;
+    self
+  }
+
+  pub fn size(&mut self, size: u8) -> &mut Self {
+    self.0.size = size;
+    self
+  }
+
+  pub fn text<T: AsRef<str>>(&mut self, text: T) -> &mut Self {
+    self.0.text = text.as_ref().to_string();
+    self
+  }
+
+  pub fn build(self) -> DataMatrix {
+    self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_defaults() {
+    let data_matrix = DataMatrix::builder().build();
+    // a square symbol sized by the printer
+    assert_eq!(data_matrix.symbol(), &DataMatrixSymbol::Square);
+    assert_eq!((data_matrix.rows(), data_matrix.columns()), (0, 0));
+    assert_eq!(data_matrix.size(), 3);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_from_json() {
+    let symbol: DataMatrixSymbol = serde_json::from_str("\"rectangle\"").unwrap();
+    assert_eq!(symbol, DataMatrixSymbol::Rectangle);
+    assert!(serde_json::from_str::<DataMatrixSymbol>("\"circle\"").is_err());
+  }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+#[cfg(feature = "graphics")]
+use image::GrayImage;
+
+/// The algorithm used to reduce a grayscale graphic to black and white dots
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum Dither {
+  /// Pixels at or below the cutoff are printed black
+  #[cfg_attr(feature = "serde", serde(rename = "threshold"))]
+  Threshold(u8),
+  #[cfg_attr(feature = "serde", serde(rename = "floyd_steinberg"))]
+  FloydSteinberg,
+  #[cfg_attr(feature = "serde", serde(rename = "atkinson"))]
+  Atkinson,
+  /// Ordered dithering with a 4x4 Bayer matrix
+  #[cfg_attr(feature = "serde", serde(rename = "bayer"))]
+  Bayer,
+}
+
+impl Default for Dither {
+  fn default() -> Self {
+    Dither::Threshold(128)
+  }
+}
+
+impl fmt::Display for Dither {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Dither::Threshold(cutoff) => write!(f, "threshold({})", cutoff),
+      Dither::FloydSteinberg => write!(f, "floyd_steinberg"),
+      Dither::Atkinson => write!(f, "atkinson"),
+      Dither::Bayer => write!(f, "bayer"),
+    }
+  }
+}
+
+#[cfg(feature = "graphics")]
+const BAYER_MATRIX: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+// (x offset, y offset, weight) of the neighbours receiving the quantisation error
+#[cfg(feature = "graphics")]
+const FLOYD_STEINBERG: [(i64, i64, f32); 4] = [
+  (1, 0, 7.0 / 16.0),
+  (-1, 1, 3.0 / 16.0),
+  (0, 1, 5.0 / 16.0),
+  (1, 1, 1.0 / 16.0),
+];
+
+#[cfg(feature = "graphics")]
+const ATKINSON: [(i64, i64, f32); 6] = [
+  (1, 0, 1.0 / 8.0),
+  (2, 0, 1.0 / 8.0),
+  (-1, 1, 1.0 / 8.0),
+  (0, 1, 1.0 / 8.0),
+  (1, 1, 1.0 / 8.0),
+  (0, 2, 1.0 / 8.0),
+];
+
+#[cfg(feature = "graphics")]
+impl Dither {
+  /// Reduces the image to pixels that are either 0 (black) or 255 (white)
+  pub(crate) fn apply(&self, img: &mut GrayImage) {
+    match self {
+      Dither::Threshold(cutoff) => {
+        for pixel in img.pixels_mut() {
+          pixel.0[0] = if pixel.0[0] <= *cutoff { 0 } else { 255 };
+        }
+      }
+      Dither::FloydSteinberg => Self::diffuse(img, &FLOYD_STEINBERG),
+      Dither::Atkinson => Self::diffuse(img, &ATKINSON),
+      Dither::Bayer => {
+        for (x, y, pixel) in img.enumerate_pixels_mut() {
+          let level = BAYER_MATRIX[(y % 4) as usize][(x % 4) as usize];
+          let threshold = u16::from(level) * 16 + 8;
+          pixel.0[0] = if u16::from(pixel.0[0]) < threshold {
+            0
+          } else {
+            255
+          };
+        }
+      }
+    }
+  }
+
+  #[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+  )]
+  fn diffuse(img: &mut GrayImage, neighbours: &[(i64, i64, f32)]) {
+    let (width, height) = (i64::from(img.width()), i64::from(img.height()));
+    let mut values: Vec<f32> = img.pixels().map(|pixel| f32::from(pixel.0[0])).collect();
+
+    for y in 0..height {
+      for x in 0..width {
+        let index = (y * width + x) as usize;
+        let old = values[index];
+        let new = if old < 128.0 { 0.0 } else { 255.0 };
+        let error = old - new;
+        values[index] = new;
+
+        for (dx, dy, weight) in neighbours {
+          let (nx, ny) = (x + dx, y + dy);
+          if nx >= 0 && nx < width && ny < height {
+            values[(ny * width + nx) as usize] += error * weight;
+          }
+        }
+      }
+    }
+
+    for (pixel, value) in img.pixels_mut().zip(values) {
+      pixel.0[0] = value as u8;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(feature = "graphics")]
+  use image::{GrayImage, Luma};
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_threshold() {
+    let mut img = GrayImage::from_fn(4, 1, |x, _| Luma([[0, 100, 128, 200][x as usize]]));
+    super::Dither::Threshold(100).apply(&mut img);
+    assert_eq!(img.into_raw(), vec![0, 0, 255, 255]);
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_mid_gray() {
+    use super::Dither;
+
+    // a flat mid gray should come out as roughly half black dots
+    for dither in [Dither::FloydSteinberg, Dither::Atkinson, Dither::Bayer] {
+      let mut img = GrayImage::from_pixel(16, 16, Luma([128]));
+      dither.apply(&mut img);
+      let black = img.pixels().filter(|pixel| pixel.0[0] == 0).count();
+      assert!((112..=144).contains(&black), "{}: {}", dither, black);
+      assert!(img
+        .pixels()
+        .all(|pixel| pixel.0[0] == 0 || pixel.0[0] == 255));
+    }
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_from_json() {
+    let dither: super::Dither = serde_json::from_str("\"atkinson\"").unwrap();
+    assert_eq!(dither, super::Dither::Atkinson);
+
+    let dither: super::Dither = serde_json::from_str(r#"{"threshold": 90}"#).unwrap();
+    assert_eq!(dither, super::Dither::Threshold(90));
+  }
+}
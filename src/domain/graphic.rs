@@ -1,11 +1,16 @@
 use std::fmt;
 
 #[cfg(feature = "graphics")]
-use image::{DynamicImage, GenericImageView, Rgba};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, GrayImage, Luma, Rgba};
 
-#[cfg(feature = "serde")]
-use serde::{de, Deserializer};
+#[cfg(all(feature = "serde", feature = "graphics"))]
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+#[cfg(all(feature = "serde", feature = "graphics"))]
+use serde::{de, ser::SerializeStruct, Deserializer, Serializer};
 
+use crate::domain::Alignment;
+#[cfg(feature = "graphics")]
+use crate::domain::Dither;
 #[cfg(feature = "graphics")]
 use crate::error::{PrinterError, Result};
 
@@ -44,55 +49,401 @@ impl From<&GraphicSize> for u8 {
   }
 }
 
+/// The command used to send graphics to the printer
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum GraphicMode {
+  /// `GS v 0` raster bit images, supported by most printers
+  #[cfg_attr(feature = "serde", serde(rename = "raster"))]
+  Raster,
+  /// `ESC *` bit images in 8-dot columns, for older printers
+  #[cfg_attr(feature = "serde", serde(rename = "column8"))]
+  Column8,
+  /// `ESC *` bit images in 24-dot columns, for older printers
+  #[cfg_attr(feature = "serde", serde(rename = "column24"))]
+  Column24,
+  /// `GS ( L` / `GS 8 L` graphics stored in the print buffer and then printed
+  #[cfg_attr(feature = "serde", serde(rename = "graphics"))]
+  Graphics,
+}
+
+impl Default for GraphicMode {
+  fn default() -> Self {
+    GraphicMode::Raster
+  }
+}
+
+impl fmt::Display for GraphicMode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GraphicMode::Raster => write!(f, "Raster"),
+      GraphicMode::Column8 => write!(f, "Column8"),
+      GraphicMode::Column24 => write!(f, "Column24"),
+      GraphicMode::Graphics => write!(f, "Graphics"),
+    }
+  }
+}
+
+/// The resolution a graphic is printed at. At half density each dot is printed twice as wide or
+/// tall, so the graphic is scaled down on that axis to keep its printed size.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum GraphicDensity {
+  #[cfg_attr(feature = "serde", serde(rename = "full"))]
+  Full,
+  #[cfg_attr(feature = "serde", serde(rename = "half_horizontal"))]
+  HalfHorizontal,
+  #[cfg_attr(feature = "serde", serde(rename = "half_vertical"))]
+  HalfVertical,
+  #[cfg_attr(feature = "serde", serde(rename = "half"))]
+  Half,
+}
+
+impl GraphicDensity {
+  /// The number of printer dots covered by one dot of the graphic horizontally
+  pub fn horizontal_scale(&self) -> u32 {
+    match self {
+      GraphicDensity::Full | GraphicDensity::HalfVertical => 1,
+      GraphicDensity::HalfHorizontal | GraphicDensity::Half => 2,
+    }
+  }
+
+  /// The number of printer dots covered by one dot of the graphic vertically
+  pub fn vertical_scale(&self) -> u32 {
+    match self {
+      GraphicDensity::Full | GraphicDensity::HalfHorizontal => 1,
+      GraphicDensity::HalfVertical | GraphicDensity::Half => 2,
+    }
+  }
+}
+
+impl Default for GraphicDensity {
+  fn default() -> Self {
+    GraphicDensity::Full
+  }
+}
+
+impl fmt::Display for GraphicDensity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GraphicDensity::Full => write!(f, "Full"),
+      GraphicDensity::HalfHorizontal => write!(f, "HalfHorizontal"),
+      GraphicDensity::HalfVertical => write!(f, "HalfVertical"),
+      GraphicDensity::Half => write!(f, "Half"),
+    }
+  }
+}
+
+impl From<&GraphicDensity> for u8 {
+  fn from(density: &GraphicDensity) -> Self {
+    match density {
+      GraphicDensity::Full => 0x00,
+      GraphicDensity::HalfHorizontal => 0x01,
+      GraphicDensity::HalfVertical => 0x02,
+      GraphicDensity::Half => 0x03,
+    }
+  }
+}
+
+/// The filter used when a graphic has to be resized
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum GraphicFilter {
+  #[cfg_attr(feature = "serde", serde(rename = "nearest"))]
+  Nearest,
+  #[cfg_attr(feature = "serde", serde(rename = "triangle"))]
+  Triangle,
+  #[cfg_attr(feature = "serde", serde(rename = "catmull_rom"))]
+  CatmullRom,
+  #[cfg_attr(feature = "serde", serde(rename = "gaussian"))]
+  Gaussian,
+  #[cfg_attr(feature = "serde", serde(rename = "lanczos3"))]
+  Lanczos3,
+}
+
+impl Default for GraphicFilter {
+  fn default() -> Self {
+    GraphicFilter::Nearest
+  }
+}
+
+impl fmt::Display for GraphicFilter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GraphicFilter::Nearest => write!(f, "Nearest"),
+      GraphicFilter::Triangle => write!(f, "Triangle"),
+      GraphicFilter::CatmullRom => write!(f, "CatmullRom"),
+      GraphicFilter::Gaussian => write!(f, "Gaussian"),
+      GraphicFilter::Lanczos3 => write!(f, "Lanczos3"),
+    }
+  }
+}
+
+#[cfg(feature = "graphics")]
+impl From<&GraphicFilter> for FilterType {
+  fn from(filter: &GraphicFilter) -> Self {
+    match filter {
+      GraphicFilter::Nearest => FilterType::Nearest,
+      GraphicFilter::Triangle => FilterType::Triangle,
+      GraphicFilter::CatmullRom => FilterType::CatmullRom,
+      GraphicFilter::Gaussian => FilterType::Gaussian,
+      GraphicFilter::Lanczos3 => FilterType::Lanczos3,
+    }
+  }
+}
+
+/// A step applied to the source image of a graphic before it is resized and dithered
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum GraphicTransform {
+  /// Rotates the image 90 degrees clockwise
+  #[cfg_attr(feature = "serde", serde(rename = "rotate90"))]
+  Rotate90,
+  #[cfg_attr(feature = "serde", serde(rename = "rotate180"))]
+  Rotate180,
+  #[cfg_attr(feature = "serde", serde(rename = "rotate270"))]
+  Rotate270,
+  /// Swaps black and white, for dark artwork
+  #[cfg_attr(feature = "serde", serde(rename = "invert"))]
+  Invert,
+  /// Removes white borders around the artwork
+  #[cfg_attr(feature = "serde", serde(rename = "trim"))]
+  Trim,
+  /// Widens the image to the maximum width with white borders, aligning the artwork within it
+  #[cfg_attr(feature = "serde", serde(rename = "pad"))]
+  Pad(Alignment),
+}
+
+impl fmt::Display for GraphicTransform {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GraphicTransform::Rotate90 => write!(f, "Rotate90"),
+      GraphicTransform::Rotate180 => write!(f, "Rotate180"),
+      GraphicTransform::Rotate270 => write!(f, "Rotate270"),
+      GraphicTransform::Invert => write!(f, "Invert"),
+      GraphicTransform::Trim => write!(f, "Trim"),
+      GraphicTransform::Pad(alignment) => write!(f, "Pad({})", alignment),
+    }
+  }
+}
+
+// pixels lighter than this are treated as background when trimming
+#[cfg(feature = "graphics")]
+const TRIM_THRESHOLD: u8 = 250;
+
 #[cfg(feature = "graphics")]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, PartialEq, Clone)]
 pub struct Graphic {
-  path: String,
-  #[cfg_attr(feature = "serde", serde(skip_serializing, skip_deserializing))]
+  path: Option<String>,
   img: DynamicImage,
-  density: u8,
+  density: GraphicDensity,
   max_width: u32,
   size: GraphicSize,
+  filter: GraphicFilter,
+  dither: Dither,
+  gamma: f32,
+  contrast: f32,
+  transforms: Vec<GraphicTransform>,
 }
 
 #[cfg(feature = "graphics")]
 impl Graphic {
-  pub fn new(path: String, density: u8, max_width: u32, size: GraphicSize) -> Result<Self> {
+  pub fn new(
+    path: String,
+    density: GraphicDensity,
+    max_width: u32,
+    size: GraphicSize,
+  ) -> Result<Self> {
     let img = image::open(&path)?;
-    let img = if img.width() > max_width {
-      let resized = img.resize(max_width, max_width, image::imageops::Nearest);
-      resized.grayscale()
-    } else {
-      img.grayscale()
-    };
-    Ok(Self {
-      path,
-      img,
+    let mut graphic = Self::from_image(img, density, max_width, size);
+    graphic.path = Some(path);
+    Ok(graphic)
+  }
+
+  /// Creates a graphic from an encoded image, such as a PNG or JPEG file read into memory
+  pub fn from_bytes(
+    bytes: &[u8],
+    density: GraphicDensity,
+    max_width: u32,
+    size: GraphicSize,
+  ) -> Result<Self> {
+    let img = image::load_from_memory(bytes)?;
+    Ok(Self::from_image(img, density, max_width, size))
+  }
+
+  /// Creates a graphic from a buffer of 8-bit grayscale pixels, row by row
+  pub fn from_gray(
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+    density: GraphicDensity,
+    max_width: u32,
+    size: GraphicSize,
+  ) -> Result<Self> {
+    let img = GrayImage::from_raw(width, height, data)
+      .ok_or_else(|| PrinterError::input("Buffer does not match the graphic dimensions"))?;
+    Ok(Self::from_image(
+      DynamicImage::ImageLuma8(img),
+      density,
+      max_width,
+      size,
+    ))
+  }
+
+  /// Creates a graphic from a buffer of 1-bit pixels, where a set bit is a black dot. Each row
+  /// starts with the most significant bit of a new byte.
+  pub fn from_mono(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    density: GraphicDensity,
+    max_width: u32,
+    size: GraphicSize,
+  ) -> Result<Self> {
+    let row_bytes = (width as usize + 7) / 8;
+    if data.len() != row_bytes * height as usize {
+      return Err(PrinterError::input(
+        "Buffer does not match the graphic dimensions",
+      ));
+    }
+    let img = GrayImage::from_fn(width, height, |x, y| {
+      let byte = data[y as usize * row_bytes + x as usize / 8];
+      if byte & (0x80 >> (x % 8)) == 0 {
+        Luma([255])
+      } else {
+        Luma([0])
+      }
+    });
+    Ok(Self::from_image(
+      DynamicImage::ImageLuma8(img),
+      density,
+      max_width,
+      size,
+    ))
+  }
+
+  /// Creates a graphic from an image. The image is only resized when it is printed.
+  pub fn from_image(
+    img: DynamicImage,
+    density: GraphicDensity,
+    max_width: u32,
+    size: GraphicSize,
+  ) -> Self {
+    Self {
+      path: None,
+      img: img.grayscale(),
       density,
       max_width,
       size,
-    })
+      filter: GraphicFilter::default(),
+      dither: Dither::default(),
+      gamma: 1.0,
+      contrast: 1.0,
+      transforms: Vec::new(),
+    }
+  }
+
+  /// Applies a transformation step to the source image
+  fn transform(&mut self, transform: GraphicTransform) -> Result<()> {
+    let img = match &transform {
+      GraphicTransform::Rotate90 => self.img.rotate90(),
+      GraphicTransform::Rotate180 => self.img.rotate180(),
+      GraphicTransform::Rotate270 => self.img.rotate270(),
+      GraphicTransform::Invert => {
+        let mut img = self.img.to_luma8();
+        image::imageops::invert(&mut img);
+        DynamicImage::ImageLuma8(img)
+      }
+      GraphicTransform::Trim => {
+        if self.img.width() == 0 || self.img.height() == 0 {
+          return Err(PrinterError::input("Cannot trim an empty graphic"));
+        }
+        let img = self.img.to_luma8();
+        let dark: Vec<(u32, u32)> = img
+          .enumerate_pixels()
+          .filter(|(_, _, pixel)| pixel.0[0] < TRIM_THRESHOLD)
+          .map(|(x, y, _)| (x, y))
+          .collect();
+        // a blank image is left as it is
+        let left = dark.iter().map(|(x, _)| *x).min().unwrap_or(0);
+        let right = dark
+          .iter()
+          .map(|(x, _)| *x)
+          .max()
+          .unwrap_or(img.width() - 1);
+        let top = dark.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let bottom = dark
+          .iter()
+          .map(|(_, y)| *y)
+          .max()
+          .unwrap_or(img.height() - 1);
+        self
+          .img
+          .crop_imm(left, top, right - left + 1, bottom - top + 1)
+      }
+      GraphicTransform::Pad(alignment) => {
+        let (width, height) = self.img.dimensions();
+        if width >= self.max_width {
+          self.img.clone()
+        } else {
+          let x = match alignment {
+            Alignment::Left => 0,
+            Alignment::Center => (self.max_width - width) / 2,
+            Alignment::Right => self.max_width - width,
+          };
+          let mut img = GrayImage::from_pixel(self.max_width, height, Luma([255]));
+          image::imageops::overlay(&mut img, &self.img.to_luma8(), i64::from(x), 0);
+          DynamicImage::ImageLuma8(img)
+        }
+      }
+    };
+    self.img = img;
+    self.transforms.push(transform);
+    Ok(())
+  }
+
+  /// The width and height in dots of the graphic as it is printed. The image is scaled down
+  /// to the maximum width, keeping its aspect ratio, and then to the density.
+  pub fn printed_dimensions(&self) -> (u32, u32) {
+    self.scaled_dimensions(
+      self.density.horizontal_scale(),
+      self.density.vertical_scale(),
+    )
+  }
+
+  /// The dimensions when each dot of the graphic covers the given number of printer dots
+  fn scaled_dimensions(&self, horizontal: u32, vertical: u32) -> (u32, u32) {
+    let (mut width, mut height) = self.img.dimensions();
+    if width > self.max_width {
+      let scaled =
+        (u64::from(height) * u64::from(self.max_width) + u64::from(width) / 2) / u64::from(width);
+      height = scaled as u32;
+      width = self.max_width;
+    }
+    (
+      ((width + horizontal - 1) / horizontal).max(1),
+      ((height + vertical - 1) / vertical).max(1),
+    )
   }
 
   pub fn width(&self) -> u16 {
-    self.img.width() as u16
+    u16::try_from(self.printed_dimensions().0).unwrap_or(u16::MAX)
   }
 
   pub fn height(&self) -> u16 {
-    self.img.height() as u16
+    u16::try_from(self.printed_dimensions().1).unwrap_or(u16::MAX)
   }
 
-  #[allow(clippy::cast_sign_loss)]
+  /// The number of bytes in each row, with the last byte padded
   pub fn width_bytes(&self) -> u16 {
-    (f32::from(self.width()) / 8.0).ceil() as u16
+    (self.width() + 7) / 8
   }
 
-  #[allow(clippy::cast_sign_loss)]
   pub fn height_bytes(&self) -> u16 {
-    (f32::from(self.height()) / 8.0).ceil() as u16
+    (self.height() + 7) / 8
   }
 
+  /// The source image, before it is resized
   pub fn img(&self) -> &DynamicImage {
     &self.img
   }
@@ -101,16 +452,22 @@ impl Graphic {
     (self.width(), self.height())
   }
 
+  /// A pixel of the source image
   pub fn pixel(&self, x: u32, y: u32) -> Rgba<u8> {
     self.img.get_pixel(x, y)
   }
 
-  pub fn density(&self) -> u8 {
-    self.density
+  pub fn density(&self) -> &GraphicDensity {
+    &self.density
   }
 
-  pub fn path(&self) -> &str {
-    &self.path
+  pub fn filter(&self) -> &GraphicFilter {
+    &self.filter
+  }
+
+  /// The path the graphic was loaded from, if it was not created in memory
+  pub fn path(&self) -> Option<&str> {
+    self.path.as_deref()
   }
 
   pub fn size(&self) -> &GraphicSize {
@@ -121,27 +478,104 @@ impl Graphic {
     self.max_width
   }
 
+  pub fn dither(&self) -> &Dither {
+    &self.dither
+  }
+
+  pub fn gamma(&self) -> f32 {
+    self.gamma
+  }
+
+  pub fn contrast(&self) -> f32 {
+    self.contrast
+  }
+
+  /// The transformation steps applied to the source image
+  pub fn transforms(&self) -> &[GraphicTransform] {
+    &self.transforms
+  }
+
+  /// The graphic as it will be printed, after resizing, gamma and contrast adjustment and
+  /// dithering. Black pixels are 0 and white pixels are 255.
+  pub fn bitmap(&self) -> GrayImage {
+    self.scaled_bitmap(
+      self.density.horizontal_scale(),
+      self.density.vertical_scale(),
+    )
+  }
+
+  /// The bitmap when each dot of the graphic covers the given number of printer dots, for
+  /// commands with a fixed resolution
+  #[allow(clippy::cast_sign_loss)]
+  pub(crate) fn scaled_bitmap(&self, horizontal: u32, vertical: u32) -> GrayImage {
+    let (width, height) = self.scaled_dimensions(horizontal, vertical);
+    let mut img = if (width, height) == self.img.dimensions() {
+      self.img.to_luma8()
+    } else {
+      self
+        .img
+        .resize_exact(width, height, (&self.filter).into())
+        .to_luma8()
+    };
+
+    #[allow(clippy::float_cmp)]
+    if self.gamma != 1.0 || self.contrast != 1.0 {
+      let mut lookup = [0u8; 256];
+      for (value, adjusted) in lookup.iter_mut().enumerate() {
+        let value = f32::from(u8::try_from(value).unwrap_or(u8::MAX)) / 255.0;
+        let value = value.powf(1.0 / self.gamma);
+        let value = (value - 0.5) * self.contrast + 0.5;
+        *adjusted = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+      }
+      for pixel in img.pixels_mut() {
+        pixel.0[0] = lookup[usize::from(pixel.0[0])];
+      }
+    }
+
+    self.dither.apply(&mut img);
+    img
+  }
+
   pub fn builder() -> GraphicBuilder {
     GraphicBuilder::default()
   }
 }
 
+#[cfg(feature = "graphics")]
+enum GraphicSource {
+  Path(String),
+  Image(DynamicImage),
+  Bytes(Vec<u8>),
+  Gray(u32, u32, Vec<u8>),
+  Mono(u32, u32, Vec<u8>),
+}
+
 #[cfg(feature = "graphics")]
 pub struct GraphicBuilder {
-  path: Option<String>,
-  density: u8,
+  source: Option<GraphicSource>,
+  density: GraphicDensity,
   max_width: u32,
   size: GraphicSize,
+  filter: GraphicFilter,
+  dither: Dither,
+  gamma: f32,
+  contrast: f32,
+  transforms: Vec<GraphicTransform>,
 }
 
 #[cfg(feature = "graphics")]
 impl Default for GraphicBuilder {
   fn default() -> Self {
     Self {
-      path: None,
-      density: 8,
+      source: None,
+      density: GraphicDensity::default(),
       max_width: 512,
       size: GraphicSize::Normal,
+      filter: GraphicFilter::default(),
+      dither: Dither::default(),
+      gamma: 1.0,
+      contrast: 1.0,
+      transforms: Vec::new(),
     }
   }
 }
@@ -150,15 +584,44 @@ impl Default for GraphicBuilder {
 impl GraphicBuilder {
   pub fn path<T: AsRef<str>>(&mut self, path: T) -> &mut Self {
     let path = path.as_ref().to_string();
-    self.path = Some(path);
+    self.source = Some(GraphicSource::Path(path));
+    self
+  }
+
+  pub fn image(&mut self, img: DynamicImage) -> &mut Self {
+    self.source = Some(GraphicSource::Image(img));
+    self
+  }
+
+  /// An encoded image, such as the contents of a PNG or JPEG file
+  pub fn bytes<T: AsRef<[u8]>>(&mut self, bytes: T) -> &mut Self {
+    self.source = Some(GraphicSource::Bytes(bytes.as_ref().to_vec()));
+    self
+  }
+
+  /// 8-bit grayscale pixels, row by row
+  pub fn gray(&mut self, width: u32, height: u32, data: Vec<u8>) -> &mut Self {
+    self.source = Some(GraphicSource::Gray(width, height, data));
+    self
+  }
+
+  /// 1-bit pixels where a set bit is a black dot, with each row padded to a whole byte
+  pub fn mono(&mut self, width: u32, height: u32, data: Vec<u8>) -> &mut Self {
+    self.source = Some(GraphicSource::Mono(width, height, data));
     self
   }
 
-  pub fn density(mut self, density: u8) -> Self {
+  pub fn density(&mut self, density: GraphicDensity) -> &mut Self {
     self.density = density;
     self
   }
 
+  /// The filter used to scale the graphic down to the maximum width and density
+  pub fn filter(&mut self, filter: GraphicFilter) -> &mut Self {
+    self.filter = filter;
+    self
+  }
+
   pub fn size(&mut self, size: GraphicSize) -> &mut Self {
     self.size = size;
     self
@@ -169,17 +632,101 @@ impl GraphicBuilder {
     self
   }
 
+  pub fn dither(&mut self, dither: Dither) -> &mut Self {
+    self.dither = dither;
+    self
+  }
+
+  /// Values above 1.0 lighten the mid tones, values below 1.0 darken them
+  pub fn gamma(&mut self, gamma: f32) -> &mut Self {
+    self.gamma = gamma;
+    self
+  }
+
+  /// Values above 1.0 increase the contrast, values below 1.0 reduce it
+  pub fn contrast(&mut self, contrast: f32) -> &mut Self {
+    self.contrast = contrast;
+    self
+  }
+
+  /// Adds a transformation step. Steps are applied in the order they are added, before the
+  /// graphic is resized and dithered.
+  pub fn transform(&mut self, transform: GraphicTransform) -> &mut Self {
+    self.transforms.push(transform);
+    self
+  }
+
   pub fn build(self) -> Result<Graphic> {
-    let path = self.path.ok_or(PrinterError::input("No path provided"))?;
-    let graphic = Graphic::new(path, self.density, self.max_width, self.size)?;
+    if self.max_width == 0 {
+      return Err(PrinterError::input("Maximum width must be greater than 0"));
+    }
+    if !(self.gamma.is_finite() && self.gamma > 0.0) {
+      return Err(PrinterError::input("Gamma must be greater than 0"));
+    }
+    if !(self.contrast.is_finite() && self.contrast >= 0.0) {
+      return Err(PrinterError::input("Contrast must not be negative"));
+    }
+    let (density, max_width, size) = (self.density, self.max_width, self.size);
+    let mut graphic = match self.source {
+      Some(GraphicSource::Path(path)) => Graphic::new(path, density, max_width, size)?,
+      Some(GraphicSource::Image(img)) => Graphic::from_image(img, density, max_width, size),
+      Some(GraphicSource::Bytes(bytes)) => Graphic::from_bytes(&bytes, density, max_width, size)?,
+      Some(GraphicSource::Gray(width, height, data)) => {
+        Graphic::from_gray(width, height, data, density, max_width, size)?
+      }
+      Some(GraphicSource::Mono(width, height, data)) => {
+        Graphic::from_mono(width, height, &data, density, max_width, size)?
+      }
+      None => return Err(PrinterError::input("No image provided")),
+    };
+    graphic.filter = self.filter;
+    graphic.dither = self.dither;
+    graphic.gamma = self.gamma;
+    graphic.contrast = self.contrast;
+    for transform in self.transforms {
+      graphic.transform(transform)?;
+    }
     Ok(graphic)
   }
 }
 
-#[cfg(feature = "serde")]
+#[cfg(all(feature = "serde", feature = "graphics"))]
+impl serde::Serialize for Graphic {
+  fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    // the options below, plus either the path and transforms or the embedded data
+    let len = if self.path.is_some() { 9 } else { 8 };
+    let mut state = serializer.serialize_struct("Graphic", len)?;
+    if let Some(path) = &self.path {
+      state.serialize_field("path", path)?;
+      // the transforms are applied again when the graphic is loaded from the path
+      state.serialize_field("transforms", &self.transforms)?;
+    } else {
+      // graphics created in memory are embedded as a base64 encoded PNG
+      let mut png = std::io::Cursor::new(Vec::new());
+      self
+        .img
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .map_err(serde::ser::Error::custom)?;
+      state.serialize_field("data", &BASE64.encode(png.into_inner()))?;
+    }
+    state.serialize_field("density", &self.density)?;
+    state.serialize_field("max_width", &self.max_width)?;
+    state.serialize_field("size", &self.size)?;
+    state.serialize_field("filter", &self.filter)?;
+    state.serialize_field("dither", &self.dither)?;
+    state.serialize_field("gamma", &self.gamma)?;
+    state.serialize_field("contrast", &self.contrast)?;
+    state.end()
+  }
+}
+
+#[cfg(all(feature = "serde", feature = "graphics"))]
 struct GraphicVisitor;
 
-#[cfg(feature = "serde")]
+#[cfg(all(feature = "serde", feature = "graphics"))]
 impl<'de> serde::de::Visitor<'de> for GraphicVisitor {
   type Value = Graphic;
 
@@ -187,23 +734,36 @@ impl<'de> serde::de::Visitor<'de> for GraphicVisitor {
     formatter.write_str("struct Graphic")
   }
 
+  #[allow(clippy::too_many_lines)]
   fn visit_map<M>(self, mut access: M) -> std::result::Result<Graphic, M::Error>
   where
     M: serde::de::MapAccess<'de>,
   {
     let mut path: Option<String> = None;
+    let mut data: Option<String> = None;
     let mut density = None;
     let mut max_width = None;
     let mut size = None;
+    let mut filter = None;
+    let mut dither = None;
+    let mut gamma = None;
+    let mut contrast = None;
+    let mut transforms: Option<Vec<GraphicTransform>> = None;
 
-    while let Some(key) = access.next_key()? {
-      match key {
+    while let Some(key) = access.next_key::<String>()? {
+      match key.as_str() {
         "path" => {
           if path.is_some() {
             return Err(de::Error::duplicate_field("path"));
           }
           path = Some(access.next_value()?);
         }
+        "data" => {
+          if data.is_some() {
+            return Err(de::Error::duplicate_field("data"));
+          }
+          data = Some(access.next_value()?);
+        }
         "density" => {
           if density.is_some() {
             return Err(de::Error::duplicate_field("density"));
@@ -222,31 +782,94 @@ impl<'de> serde::de::Visitor<'de> for GraphicVisitor {
           }
           size = Some(access.next_value()?);
         }
+        "filter" => {
+          if filter.is_some() {
+            return Err(de::Error::duplicate_field("filter"));
+          }
+          filter = Some(access.next_value()?);
+        }
+        "dither" => {
+          if dither.is_some() {
+            return Err(de::Error::duplicate_field("dither"));
+          }
+          dither = Some(access.next_value()?);
+        }
+        "gamma" => {
+          if gamma.is_some() {
+            return Err(de::Error::duplicate_field("gamma"));
+          }
+          gamma = Some(access.next_value()?);
+        }
+        "contrast" => {
+          if contrast.is_some() {
+            return Err(de::Error::duplicate_field("contrast"));
+          }
+          contrast = Some(access.next_value()?);
+        }
+        "transforms" => {
+          if transforms.is_some() {
+            return Err(de::Error::duplicate_field("transforms"));
+          }
+          transforms = Some(access.next_value()?);
+        }
         _ => {
           return Err(de::Error::unknown_field(
-            key,
-            &["path", "density", "max_width", "size"],
+            &key,
+            &[
+              "path",
+              "data",
+              "density",
+              "max_width",
+              "size",
+              "filter",
+              "dither",
+              "gamma",
+              "contrast",
+              "transforms",
+            ],
           ));
         }
       }
     }
-    let path = path.ok_or_else(|| de::Error::missing_field("path"))?;
-    let density = density.ok_or_else(|| de::Error::missing_field("density"))?;
     let max_width = max_width.ok_or_else(|| de::Error::missing_field("max_width"))?;
     let size = size.ok_or_else(|| de::Error::missing_field("size"))?;
 
-    if let Ok(graphic) = Graphic::new(path.clone(), density, max_width, size) {
-      Ok(graphic)
-    } else {
-      Err(de::Error::custom(format!(
-        "Could not load graphic at path: {}",
-        path
-      )))
+    let mut builder = Graphic::builder();
+    match (path, data) {
+      (Some(path), None) => builder.path(path),
+      (None, Some(data)) => {
+        let bytes = BASE64.decode(data).map_err(de::Error::custom)?;
+        builder.bytes(bytes)
+      }
+      (Some(_), Some(_)) => {
+        return Err(de::Error::custom("expected only one of `path` or `data`"));
+      }
+      (None, None) => return Err(de::Error::missing_field("path")),
+    };
+    builder
+      .density(density.unwrap_or_default())
+      .max_width(max_width)
+      .size(size)
+      .filter(filter.unwrap_or_default())
+      .dither(dither.unwrap_or_default())
+      .gamma(gamma.unwrap_or(1.0))
+      .contrast(contrast.unwrap_or(1.0));
+    for transform in transforms.unwrap_or_default() {
+      builder.transform(transform);
+    }
+
+    match builder.build() {
+      Ok(graphic) => Ok(graphic),
+      Err(PrinterError::Input(message)) => Err(de::Error::custom(message)),
+      Err(err) => Err(de::Error::custom(format!(
+        "Could not load graphic: {}",
+        err
+      ))),
     }
   }
 }
 
-#[cfg(feature = "serde")]
+#[cfg(all(feature = "serde", feature = "graphics"))]
 impl<'de> serde::de::Deserialize<'de> for Graphic {
   fn deserialize<D>(deserializer: D) -> std::result::Result<Graphic, D::Error>
   where
@@ -260,6 +883,16 @@ impl<'de> serde::de::Deserialize<'de> for Graphic {
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_graphic_density() {
+    assert_eq!(u8::from(&GraphicDensity::Full), 0x00);
+    assert_eq!(u8::from(&GraphicDensity::HalfHorizontal), 0x01);
+    assert_eq!(u8::from(&GraphicDensity::HalfVertical), 0x02);
+    assert_eq!(u8::from(&GraphicDensity::Half), 0x03);
+    assert_eq!(GraphicDensity::HalfVertical.horizontal_scale(), 1);
+    assert_eq!(GraphicDensity::HalfVertical.vertical_scale(), 2);
+  }
+
   #[test]
   fn test_graphic_size() {
     let normal = GraphicSize::Normal;
@@ -274,24 +907,294 @@ mod tests {
   }
 
   #[test]
-  #[cfg(feature = "serde")]
+  #[cfg(all(feature = "serde", feature = "graphics"))]
   fn test_serialize_from_json() -> Result<()> {
     let json = r#"
       {
         "path": "resources/rust-logo-small.png",
-        "density": 8,
+        "density": "full",
         "max_width": 512,
         "size": "normal"
       }
     "#;
     let graphic: Graphic = serde_json::from_str(json).unwrap();
 
-    assert_eq!(graphic.path(), "resources/rust-logo-small.png");
-    assert_eq!(graphic.density(), 8);
+    assert_eq!(graphic.path(), Some("resources/rust-logo-small.png"));
+    assert_eq!(graphic.density(), &GraphicDensity::Full);
     assert_eq!(graphic.max_width(), 512);
     assert_eq!(graphic.width(), 200);
     assert_eq!(graphic.height(), 200);
     assert_eq!(graphic.size(), &GraphicSize::Normal);
+    assert_eq!(graphic.dither(), &Dither::Threshold(128));
+
+    let json = r#"
+      {
+        "path": "resources/rust-logo-small.png",
+        "density": "half",
+        "max_width": 512,
+        "size": "normal",
+        "filter": "lanczos3",
+        "dither": "floyd_steinberg",
+        "gamma": 1.8,
+        "contrast": 1.2
+      }
+    "#;
+    let graphic: Graphic = serde_json::from_str(json).unwrap();
+
+    assert_eq!(graphic.density(), &GraphicDensity::Half);
+    assert_eq!(graphic.filter(), &GraphicFilter::Lanczos3);
+    assert_eq!(graphic.dither(), &Dither::FloydSteinberg);
+    assert!((graphic.gamma() - 1.8).abs() < f32::EPSILON);
+    assert!((graphic.contrast() - 1.2).abs() < f32::EPSILON);
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(all(feature = "serde", feature = "graphics"))]
+  fn test_serialize_data() -> Result<()> {
+    let mut builder = Graphic::builder();
+    builder.mono(16, 2, vec![0xF0, 0x0F, 0xFF, 0x00]);
+    let graphic = builder.build()?;
+
+    let json = serde_json::to_value(&graphic).unwrap();
+    assert!(json.get("path").is_none());
+    assert!(json["data"].is_string());
+
+    let decoded: Graphic = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.path(), None);
+    assert_eq!(decoded.bitmap(), graphic.bitmap());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_in_memory_sources() -> Result<()> {
+    let bytes = std::fs::read("resources/rust-logo-small.png")?;
+    let mut builder = Graphic::builder();
+    builder.bytes(&bytes);
+    let graphic = builder.build()?;
+    assert_eq!(graphic.path(), None);
+    assert_eq!(graphic.dimensions(), (200, 200));
+
+    let mut builder = Graphic::builder();
+    builder.image(image::load_from_memory(&bytes)?);
+    assert_eq!(builder.build()?.bitmap(), graphic.bitmap());
+
+    let mut builder = Graphic::builder();
+    builder.gray(3, 1, vec![0, 128, 255]);
+    let graphic = builder.build()?;
+    assert_eq!(graphic.bitmap().into_raw(), vec![0, 0, 255]);
+
+    let mut builder = Graphic::builder();
+    builder.mono(10, 1, vec![0b1010_0000, 0b0100_0000]);
+    let graphic = builder.build()?;
+    assert_eq!(
+      graphic.bitmap().into_raw(),
+      vec![0, 255, 0, 255, 255, 255, 255, 255, 255, 0]
+    );
+
+    let mut builder = Graphic::builder();
+    builder.gray(3, 2, vec![0, 128, 255]);
+    assert!(builder.build().is_err());
+
+    let mut builder = Graphic::builder();
+    builder.mono(10, 1, vec![0xFF]);
+    assert!(builder.build().is_err());
+
+    assert!(Graphic::builder().build().is_err());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_transforms() -> Result<()> {
+    // a 3x2 image with a black dot in the top left corner
+    let build = |transforms: &[GraphicTransform]| {
+      let mut builder = Graphic::builder();
+      builder
+        .gray(3, 2, vec![0, 255, 255, 255, 255, 255])
+        .max_width(6);
+      for transform in transforms {
+        builder.transform(transform.clone());
+      }
+      builder.build().map(|graphic| graphic.bitmap().into_raw())
+    };
+
+    assert_eq!(
+      build(&[GraphicTransform::Rotate90])?,
+      vec![255, 0, 255, 255, 255, 255]
+    );
+    assert_eq!(
+      build(&[GraphicTransform::Rotate180])?,
+      vec![255, 255, 255, 255, 255, 0]
+    );
+    assert_eq!(
+      build(&[GraphicTransform::Rotate270])?,
+      vec![255, 255, 255, 255, 0, 255]
+    );
+    assert_eq!(
+      build(&[GraphicTransform::Invert])?,
+      vec![255, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(build(&[GraphicTransform::Trim])?, vec![0]);
+    assert_eq!(
+      build(&[
+        GraphicTransform::Trim,
+        GraphicTransform::Pad(Alignment::Center)
+      ])?,
+      vec![255, 255, 0, 255, 255, 255]
+    );
+    assert_eq!(
+      build(&[GraphicTransform::Pad(Alignment::Right)])?,
+      vec![255, 255, 255, 0, 255, 255, 255, 255, 255, 255, 255, 255]
+    );
+
+    // blank images are not trimmed
+    let mut builder = Graphic::builder();
+    builder
+      .gray(2, 2, vec![255; 4])
+      .transform(GraphicTransform::Trim);
+    assert_eq!(builder.build()?.dimensions(), (2, 2));
+
+    let mut builder = Graphic::builder();
+    builder
+      .gray(0, 0, Vec::new())
+      .transform(GraphicTransform::Trim);
+    assert!(builder.build().is_err());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(all(feature = "serde", feature = "graphics"))]
+  fn test_serialize_transforms() -> Result<()> {
+    let json = r#"
+      {
+        "path": "resources/rust-logo-small.png",
+        "max_width": 512,
+        "size": "normal",
+        "transforms": ["trim", "rotate90", {"pad": "center"}]
+      }
+    "#;
+    let graphic: Graphic = serde_json::from_str(json).unwrap();
+    assert_eq!(
+      graphic.transforms(),
+      &[
+        GraphicTransform::Trim,
+        GraphicTransform::Rotate90,
+        GraphicTransform::Pad(Alignment::Center)
+      ]
+    );
+    assert_eq!(graphic.width(), 512);
+
+    let decoded: Graphic = serde_json::from_value(serde_json::to_value(&graphic).unwrap()).unwrap();
+    assert_eq!(decoded.bitmap(), graphic.bitmap());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_printed_dimensions() -> Result<()> {
+    // wide images are scaled down to the maximum width, keeping their aspect ratio
+    let mut builder = Graphic::builder();
+    builder.gray(100, 50, vec![0; 5000]).max_width(40);
+    let graphic = builder.build()?;
+    assert_eq!(graphic.dimensions(), (40, 20));
+    assert_eq!(graphic.bitmap().dimensions(), (40, 20));
+
+    // tall images are not limited by the maximum width
+    let mut builder = Graphic::builder();
+    builder.gray(50, 300, vec![0; 15000]).max_width(25);
+    assert_eq!(builder.build()?.dimensions(), (25, 150));
+
+    let mut builder = Graphic::builder();
+    builder
+      .gray(13, 7, vec![0; 91])
+      .density(GraphicDensity::HalfHorizontal);
+    let graphic = builder.build()?;
+    assert_eq!(graphic.dimensions(), (7, 7));
+    assert_eq!(graphic.width_bytes(), 1);
+
+    let mut builder = Graphic::builder();
+    builder
+      .gray(13, 7, vec![0; 91])
+      .density(GraphicDensity::Half);
+    assert_eq!(builder.build()?.bitmap().dimensions(), (7, 4));
+
+    let mut builder = Graphic::builder();
+    builder.gray(13, 7, vec![0; 91]).max_width(0);
+    assert!(builder.build().is_err());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_filter() -> Result<()> {
+    // a thin line disappears with nearest neighbour but leaves a trace when averaged
+    let data = (0..64).map(|i| if i % 8 == 1 { 0 } else { 255 }).collect();
+
+    let mut builder = Graphic::builder();
+    builder
+      .gray(8, 8, data)
+      .max_width(4)
+      .dither(Dither::Threshold(254));
+    let nearest = builder.build()?.bitmap();
+
+    let data = (0..64).map(|i| if i % 8 == 1 { 0 } else { 255 }).collect();
+    let mut builder = Graphic::builder();
+    builder
+      .gray(8, 8, data)
+      .max_width(4)
+      .filter(GraphicFilter::Triangle)
+      .dither(Dither::Threshold(254));
+    let triangle = builder.build()?.bitmap();
+
+    assert_ne!(nearest, triangle);
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_invalid_adjustments() {
+    let mut builder = Graphic::builder();
+    builder.path("resources/rust-logo-small.png").gamma(0.0);
+    assert!(builder.build().is_err());
+
+    let mut builder = Graphic::builder();
+    builder.path("resources/rust-logo-small.png").contrast(-1.0);
+    assert!(builder.build().is_err());
+  }
+
+  /// Compares the dithered logo with the golden images in `resources/golden`.
+  /// Set `RECIBO_UPDATE_GOLDEN` to regenerate them.
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_dither_golden() -> Result<()> {
+    let cases = [
+      ("threshold", Dither::Threshold(128), 1.0, 1.0),
+      ("threshold-160", Dither::Threshold(160), 1.0, 1.0),
+      ("floyd-steinberg", Dither::FloydSteinberg, 1.0, 1.0),
+      ("atkinson", Dither::Atkinson, 1.0, 1.0),
+      ("bayer", Dither::Bayer, 1.0, 1.0),
+      ("floyd-steinberg-adjusted", Dither::FloydSteinberg, 1.8, 1.5),
+    ];
+
+    for (name, dither, gamma, contrast) in cases {
+      let mut builder = Graphic::builder();
+      builder
+        .path("resources/rust-logo.png")
+        .max_width(256)
+        .dither(dither)
+        .gamma(gamma)
+        .contrast(contrast);
+      let bitmap = builder.build()?.bitmap();
+
+      let path = format!("resources/golden/rust-logo-{}.png", name);
+      if std::env::var_os("RECIBO_UPDATE_GOLDEN").is_some() {
+        bitmap.save(&path)?;
+      }
+      let golden = image::open(&path)?.to_luma8();
+      assert_eq!(bitmap, golden, "{} does not match", path);
+    }
     Ok(())
   }
 }
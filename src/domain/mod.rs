@@ -1,15 +1,33 @@
 pub use alignment::*;
+pub use asb::*;
+pub use aztec::*;
 pub use barcode::*;
+pub use capabilities::*;
+pub use data_matrix::*;
+pub use dither::*;
 pub use font::*;
 pub use graphic::*;
 pub use hardware::*;
+pub use pdf417::*;
 pub use qr::*;
+pub use status::*;
+#[cfg(feature = "fonts")]
+pub use text_image::*;
 pub use underline_mode::*;
 
 mod alignment;
+mod asb;
+mod aztec;
 mod barcode;
+mod capabilities;
+mod data_matrix;
+mod dither;
 mod font;
 mod graphic;
 mod hardware;
+mod pdf417;
 mod qr;
+mod status;
+#[cfg(feature = "fonts")]
+mod text_image;
 mod underline_mode;
@@ -0,0 +1,173 @@
+use std::fmt;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct Pdf417 {
+  columns: u8,
+  rows: u8,
+  module_width: u8,
+  row_height: u8,
+  correction_level: u8,
+  truncated: bool,
+  text: String,
+}
+
+impl fmt::Display for Pdf417 {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "columns: {}, rows: {}, module_width: {}, row_height: {}, correction_level: {}, truncated: {}, text: {}",
+      self.columns,
+      self.rows,
+      self.module_width,
+      self.row_height,
+      self.correction_level,
+      self.truncated,
+      self.text
+    )
+  }
+}
+
+impl Pdf417 {
+  pub fn new(
+    columns: u8,
+    rows: u8,
+    module_width: u8,
+    row_height: u8,
+    correction_level: u8,
+    truncated: bool,
+    text: String,
+  ) -> Self {
+    Self {
+      columns,
+      rows,
+      module_width,
+      row_height,
+      correction_level,
+      truncated,
+      text,
+    }
+  }
+
+  /// The number of data columns, 0 lets the printer decide
+  pub fn columns(&self) -> u8 {
+    self.columns
+  }
+
+  /// The number of rows, 0 lets the printer decide
+  pub fn rows(&self) -> u8 {
+    self.rows
+  }
+
+  pub fn module_width(&self) -> u8 {
+    self.module_width
+  }
+
+  /// The row height as a multiple of the module width
+  pub fn row_height(&self) -> u8 {
+    self.row_height
+  }
+
+  pub fn correction_level(&self) -> u8 {
+    self.correction_level
+  }
+
+  pub fn truncated(&self) -> bool {
+    self.truncated
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn builder() -> Pdf417Builder {
+    Pdf417Builder::default()
+  }
+}
+
+impl Default for Pdf417 {
+  fn default() -> Self {
+    Self::new(0, 0, 3, 3, 1, false, String::new())
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Pdf417Builder(Pdf417);
+
+impl Pdf417Builder {
+  pub fn columns(&mut self, columns: u8) -> &mut Self {
+    self.0.columns = columns;
+    self
+  }
+
+  pub fn rows(&mut self, rows: u8) -> &mut Self {
+    self.0.rows = rows;
+    self
+  }
+
+  pub fn module_width(&mut self, module_width: u8) -> &mut Self {
+    self.0.module_width = module_width;
+    self
+  }
+
+  pub fn row_height(&mut self, row_height: u8) -> &mut Self {
+    self.0.row_height = row_height;
+    self
+  }
+
+  pub fn correction_level(&mut self, correction_level: u8) -> &mut Self {
+    self.0.correction_level = correction_level;
+    self
+  }
+
+  pub fn truncated(&mut self, truncated: bool) -> &mut Self {
+    self.0.truncated = truncated;
+    self
+  }
+
+  pub fn text<T: AsRef<str>>(&mut self, text: T) -> &mut Self {
+    self.0.text = text.as_ref().to_string();
+    self
+  }
+
+  pub fn build(self) -> Pdf417 {
+    self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_defaults() {
+    let pdf417 = Pdf417::builder().build();
+    // the printer picks the number of columns and rows
+    assert_eq!((pdf417.columns(), pdf417.rows()), (0, 0));
+    assert_eq!((pdf417.module_width(), pdf417.row_height()), (3, 3));
+    assert_eq!(pdf417.correction_level(), 1);
+    assert!(!pdf417.truncated());
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_from_json() {
+    let json = r#"
+      {
+        "columns": 4,
+        "rows": 0,
+        "module_width": 2,
+        "row_height": 3,
+        "correction_level": 2,
+        "truncated": true,
+        "text": "Hello World!"
+      }
+      "#;
+    let pdf417: Pdf417 = serde_json::from_str(json).unwrap();
+    assert!(pdf417.truncated());
+
+    // the column and row counts are single bytes
+    let json = json.replace("\"columns\": 4", "\"columns\": 256");
+    assert!(serde_json::from_str::<Pdf417>(&json).is_err());
+  }
+}
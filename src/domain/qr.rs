@@ -72,7 +72,17 @@ pub struct Qr {
   model: QrModel,
   correction_level: QrCorrectionLevel,
   size: u8,
+  #[cfg_attr(feature = "serde", serde(default))]
   text: String,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  bytes: Option<Vec<u8>>,
+  #[cfg_attr(feature = "serde", serde(default))]
+  raster: bool,
+  #[cfg_attr(feature = "serde", serde(default = "Qr::default_quiet_zone"))]
+  quiet_zone: u8,
 }
 
 impl fmt::Display for Qr {
@@ -92,9 +102,16 @@ impl Qr {
       correction_level,
       size,
       text,
+      bytes: None,
+      raster: false,
+      quiet_zone: Self::default_quiet_zone(),
     }
   }
 
+  fn default_quiet_zone() -> u8 {
+    4
+  }
+
   pub fn model(&self) -> &QrModel {
     &self.model
   }
@@ -111,6 +128,70 @@ impl Qr {
     &self.text
   }
 
+  /// The raw payload, used instead of the text when set
+  pub fn bytes(&self) -> Option<&[u8]> {
+    self.bytes.as_deref()
+  }
+
+  /// Whether the QR code is generated by the library and printed as a graphic,
+  /// for printers that do not support the QR code commands
+  pub fn raster(&self) -> bool {
+    self.raster
+  }
+
+  /// The number of blank modules around a rasterised QR code
+  pub fn quiet_zone(&self) -> u8 {
+    self.quiet_zone
+  }
+
+  /// The maximum number of bytes the symbol can hold for the given payload. The
+  /// printer picks the most compact encoding mode, so the capacity depends on
+  /// whether the payload is numeric, alphanumeric, Kanji or binary.
+  pub(crate) fn capacity(&self, data: &[u8]) -> usize {
+    let level = match self.correction_level {
+      QrCorrectionLevel::Low => 0,
+      QrCorrectionLevel::Medium => 1,
+      QrCorrectionLevel::Quartile => 2,
+      QrCorrectionLevel::High => 3,
+    };
+
+    // the capacity of the largest symbol (version 40 for model 2, version 14 for model 1)
+    // per correction level for the numeric, alphanumeric, binary and Kanji modes
+    let capacities: [[usize; 4]; 4] = match self.model {
+      QrModel::Model1 => [
+        [1167, 707, 486, 299],
+        [919, 557, 382, 236],
+        [703, 426, 292, 180],
+        [487, 295, 203, 125],
+      ],
+      QrModel::Model2 => [
+        [7089, 4296, 2953, 1817],
+        [5596, 3391, 2331, 1435],
+        [3993, 2420, 1663, 1024],
+        [3057, 1852, 1273, 784],
+      ],
+    };
+
+    let capacity = capacities[level];
+    let alphanumeric =
+      |c: &u8| c.is_ascii_digit() || c.is_ascii_uppercase() || b" $%*+-./:".contains(c);
+    let kanji = |pair: &[u8]| {
+      let code = u16::from_be_bytes([pair[0], pair[1]]);
+      (0x8140..=0x9FFC).contains(&code) || (0xE040..=0xEBBF).contains(&code)
+    };
+
+    if data.iter().all(u8::is_ascii_digit) {
+      capacity[0]
+    } else if data.iter().all(alphanumeric) {
+      capacity[1]
+    } else if data.len() % 2 == 0 && data.chunks(2).all(kanji) {
+      // Kanji characters are two bytes each
+      capacity[3] * 2
+    } else {
+      capacity[2]
+    }
+  }
+
   pub fn builder() -> QrBuilder {
     QrBuilder::default()
   }
@@ -143,6 +224,27 @@ impl QrBuilder {
 
   pub fn text<T: AsRef<str>>(&mut self, text: T) -> &mut Self {
     self.0.text = text.as_ref().to_string();
+    self.0.bytes = None;
+    self
+  }
+
+  /// Sets a raw payload, e.g. binary data or Shift JIS encoded Kanji, which is sent
+  /// to the printer as is
+  pub fn bytes<T: AsRef<[u8]>>(&mut self, bytes: T) -> &mut Self {
+    self.0.bytes = Some(bytes.as_ref().to_vec());
+    self
+  }
+
+  /// Generates the QR code in the library and prints it as a graphic. The size is
+  /// used as the number of dots per module, lowered when the symbol would be wider
+  /// than the 512 dots of 80 mm paper. Requires the `graphics` feature.
+  pub fn raster(&mut self, raster: bool) -> &mut Self {
+    self.0.raster = raster;
+    self
+  }
+
+  pub fn quiet_zone(&mut self, quiet_zone: u8) -> &mut Self {
+    self.0.quiet_zone = quiet_zone;
     self
   }
 
@@ -173,5 +275,60 @@ mod tests {
     assert_eq!(qr.correction_level(), &super::QrCorrectionLevel::Medium);
     assert_eq!(qr.size(), 8);
     assert_eq!(qr.text(), "Hello World!");
+    assert_eq!(qr.bytes(), None);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_bytes_from_json() {
+    let json = r#"
+      {
+        "model": "model2",
+        "correction_level": "low",
+        "size": 4,
+        "bytes": [0, 1, 255]
+      }
+      "#;
+
+    let qr: super::Qr = serde_json::from_str(json).unwrap();
+
+    assert_eq!(qr.text(), "");
+    assert_eq!(qr.bytes(), Some(&[0, 1, 255][..]));
+    assert!(!qr.raster());
+    assert_eq!(qr.quiet_zone(), 4);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_raster_from_json() {
+    let json = r#"
+      {
+        "model": "model2",
+        "correction_level": "low",
+        "size": 4,
+        "text": "Hello World!",
+        "raster": true,
+        "quiet_zone": 2
+      }
+      "#;
+
+    let qr: super::Qr = serde_json::from_str(json).unwrap();
+
+    assert!(qr.raster());
+    assert_eq!(qr.quiet_zone(), 2);
+  }
+
+  #[test]
+  fn test_capacity() {
+    let mut builder = super::Qr::builder();
+    builder
+      .model(super::QrModel::Model2)
+      .correction_level(super::QrCorrectionLevel::Low);
+    let qr = builder.build();
+
+    assert_eq!(qr.capacity(b"0123456789"), 7089);
+    assert_eq!(qr.capacity(b"HELLO WORLD"), 4296);
+    assert_eq!(qr.capacity(b"Hello world"), 2953);
+    assert_eq!(qr.capacity(&[0x93, 0xFA, 0x96, 0x7B]), 3634);
   }
 }
@@ -0,0 +1,379 @@
+use std::fmt;
+
+use crate::error::{PrinterError, Result};
+
+/// The status requested with the real-time `DLE EOT` command
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum StatusKind {
+  /// `DLE EOT 1`
+  #[cfg_attr(feature = "serde", serde(rename = "printer"))]
+  Printer,
+  /// `DLE EOT 2`, why the printer is offline
+  #[cfg_attr(feature = "serde", serde(rename = "offline"))]
+  Offline,
+  /// `DLE EOT 3`, the errors that have occurred
+  #[cfg_attr(feature = "serde", serde(rename = "error"))]
+  Error,
+  /// `DLE EOT 4`, the roll paper sensors
+  #[cfg_attr(feature = "serde", serde(rename = "paper"))]
+  Paper,
+  /// `DLE EOT 7 1`, for printers with ink cartridges
+  #[cfg_attr(feature = "serde", serde(rename = "ink_near_end"))]
+  InkNearEnd,
+  /// `DLE EOT 7 2`, for printers with ink cartridges
+  #[cfg_attr(feature = "serde", serde(rename = "ink_end"))]
+  InkEnd,
+  /// `DLE EOT 8 3`, for printers with a label peeler
+  #[cfg_attr(feature = "serde", serde(rename = "peeler"))]
+  Peeler,
+}
+
+impl fmt::Display for StatusKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StatusKind::Printer => write!(f, "printer"),
+      StatusKind::Offline => write!(f, "offline"),
+      StatusKind::Error => write!(f, "error"),
+      StatusKind::Paper => write!(f, "paper"),
+      StatusKind::InkNearEnd => write!(f, "ink_near_end"),
+      StatusKind::InkEnd => write!(f, "ink_end"),
+      StatusKind::Peeler => write!(f, "peeler"),
+    }
+  }
+}
+
+impl StatusKind {
+  /// The parameters sent after `DLE EOT`
+  pub(crate) fn parameters(&self) -> &'static [u8] {
+    match self {
+      StatusKind::Printer => &[0x01],
+      StatusKind::Offline => &[0x02],
+      StatusKind::Error => &[0x03],
+      StatusKind::Paper => &[0x04],
+      StatusKind::InkNearEnd => &[0x07, 0x01],
+      StatusKind::InkEnd => &[0x07, 0x02],
+      StatusKind::Peeler => &[0x08, 0x03],
+    }
+  }
+
+  /// Decodes the status byte sent in reply
+  pub fn parse(&self, byte: u8) -> Result<Status> {
+    // bits 1 and 4 are always set and bits 0 and 7 always clear
+    if byte & 0x93 != 0x12 {
+      return Err(PrinterError::Io(format!(
+        "Unexpected reply to the {} status request: {:#04x}",
+        self, byte
+      )));
+    }
+    let bit = |n: u8| byte & (1 << n) != 0;
+
+    Ok(match self {
+      StatusKind::Printer => Status::Printer(PrinterStatus {
+        drawer_pin_high: bit(2),
+        offline: bit(3),
+        waiting_for_recovery: bit(5),
+        feed_button: bit(6),
+      }),
+      StatusKind::Offline => Status::Offline(OfflineStatus {
+        cover_open: bit(2),
+        feeding: bit(3),
+        paper_end: bit(5),
+        error: bit(6),
+      }),
+      StatusKind::Error => Status::Error(ErrorStatus {
+        recoverable: bit(2),
+        cutter: bit(3),
+        unrecoverable: bit(5),
+        auto_recoverable: bit(6),
+      }),
+      StatusKind::Paper => Status::Paper(PaperStatus {
+        near_end: byte & 0x0C == 0x0C,
+        paper_end: byte & 0x60 == 0x60,
+      }),
+      StatusKind::InkNearEnd => Status::InkNearEnd(InkStatus {
+        first: bit(2),
+        second: bit(3),
+      }),
+      StatusKind::InkEnd => Status::InkEnd(InkStatus {
+        first: bit(2),
+        second: bit(3),
+      }),
+      StatusKind::Peeler => Status::Peeler(PeelerStatus {
+        waiting_for_removal: bit(2),
+      }),
+    })
+  }
+}
+
+/// A status reported by the printer
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum Status {
+  #[cfg_attr(feature = "serde", serde(rename = "printer"))]
+  Printer(PrinterStatus),
+  #[cfg_attr(feature = "serde", serde(rename = "offline"))]
+  Offline(OfflineStatus),
+  #[cfg_attr(feature = "serde", serde(rename = "error"))]
+  Error(ErrorStatus),
+  #[cfg_attr(feature = "serde", serde(rename = "paper"))]
+  Paper(PaperStatus),
+  #[cfg_attr(feature = "serde", serde(rename = "ink_near_end"))]
+  InkNearEnd(InkStatus),
+  #[cfg_attr(feature = "serde", serde(rename = "ink_end"))]
+  InkEnd(InkStatus),
+  #[cfg_attr(feature = "serde", serde(rename = "peeler"))]
+  Peeler(PeelerStatus),
+}
+
+impl Status {
+  /// Returns an error if the status shows the printer cannot print
+  pub fn check(&self) -> Result<()> {
+    let error = match self {
+      Status::Printer(status) if status.offline => Some(PrinterError::Offline),
+      Status::Offline(status) if status.cover_open => Some(PrinterError::CoverOpen),
+      Status::Offline(status) if status.paper_end => Some(PrinterError::PaperEnd),
+      Status::Offline(status) if status.error => Some(PrinterError::Offline),
+      Status::Error(status) if status.unrecoverable => Some(PrinterError::UnrecoverableError),
+      Status::Error(status) if status.cutter => Some(PrinterError::CutterError),
+      Status::Error(status) if status.recoverable || status.auto_recoverable => {
+        Some(PrinterError::RecoverableError)
+      }
+      Status::Paper(status) if status.paper_end => Some(PrinterError::PaperEnd),
+      Status::InkEnd(status) if status.first || status.second => Some(PrinterError::InkEnd),
+      _ => None,
+    };
+    error.map_or(Ok(()), Err)
+  }
+}
+
+/// The reply to `DLE EOT 1`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct PrinterStatus {
+  drawer_pin_high: bool,
+  offline: bool,
+  waiting_for_recovery: bool,
+  feed_button: bool,
+}
+
+impl PrinterStatus {
+  /// Whether pin 3 of the drawer kick-out connector is high. Which level means the drawer is
+  /// open depends on the drawer.
+  pub fn drawer_pin_high(&self) -> bool {
+    self.drawer_pin_high
+  }
+
+  pub fn offline(&self) -> bool {
+    self.offline
+  }
+
+  /// Whether the printer is waiting to come back online after an error
+  pub fn waiting_for_recovery(&self) -> bool {
+    self.waiting_for_recovery
+  }
+
+  /// Whether the paper feed button is pressed
+  pub fn feed_button(&self) -> bool {
+    self.feed_button
+  }
+}
+
+/// The reply to `DLE EOT 2`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct OfflineStatus {
+  cover_open: bool,
+  feeding: bool,
+  paper_end: bool,
+  error: bool,
+}
+
+impl OfflineStatus {
+  pub fn cover_open(&self) -> bool {
+    self.cover_open
+  }
+
+  /// Whether paper is being fed with the paper feed button
+  pub fn feeding(&self) -> bool {
+    self.feeding
+  }
+
+  /// Whether printing stopped because the paper ran out
+  pub fn paper_end(&self) -> bool {
+    self.paper_end
+  }
+
+  pub fn error(&self) -> bool {
+    self.error
+  }
+}
+
+/// The reply to `DLE EOT 3`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ErrorStatus {
+  recoverable: bool,
+  cutter: bool,
+  unrecoverable: bool,
+  auto_recoverable: bool,
+}
+
+impl ErrorStatus {
+  /// Whether an error has occurred that can be cleared with `DLE ENQ`
+  pub fn recoverable(&self) -> bool {
+    self.recoverable
+  }
+
+  /// Whether the autocutter has jammed
+  pub fn cutter(&self) -> bool {
+    self.cutter
+  }
+
+  /// Whether an error has occurred that needs the printer to be turned off and on
+  pub fn unrecoverable(&self) -> bool {
+    self.unrecoverable
+  }
+
+  /// Whether an error has occurred that clears by itself, such as the print head overheating
+  pub fn auto_recoverable(&self) -> bool {
+    self.auto_recoverable
+  }
+}
+
+/// The reply to `DLE EOT 4`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct PaperStatus {
+  near_end: bool,
+  paper_end: bool,
+}
+
+impl PaperStatus {
+  pub fn near_end(&self) -> bool {
+    self.near_end
+  }
+
+  pub fn paper_end(&self) -> bool {
+    self.paper_end
+  }
+}
+
+/// The reply to `DLE EOT 7`, for the first and second ink colours
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct InkStatus {
+  first: bool,
+  second: bool,
+}
+
+impl InkStatus {
+  pub fn first(&self) -> bool {
+    self.first
+  }
+
+  pub fn second(&self) -> bool {
+    self.second
+  }
+}
+
+/// The reply to `DLE EOT 8`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct PeelerStatus {
+  waiting_for_removal: bool,
+}
+
+impl PeelerStatus {
+  /// Whether a peeled label is waiting to be removed
+  pub fn waiting_for_removal(&self) -> bool {
+    self.waiting_for_removal
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse() -> Result<()> {
+    let status = StatusKind::Printer.parse(0x16)?;
+    assert_eq!(
+      status,
+      Status::Printer(PrinterStatus {
+        drawer_pin_high: true,
+        offline: false,
+        waiting_for_recovery: false,
+        feed_button: false,
+      })
+    );
+
+    match StatusKind::Offline.parse(0x36)? {
+      Status::Offline(offline) => {
+        assert!(offline.cover_open() && offline.paper_end());
+        assert!(!offline.feeding() && !offline.error());
+      }
+      status => panic!("expected an offline status, got {:?}", status),
+    }
+
+    match StatusKind::Error.parse(0x1A)? {
+      Status::Error(error) => {
+        assert!(error.cutter());
+        assert!(!error.recoverable() && !error.unrecoverable() && !error.auto_recoverable());
+      }
+      status => panic!("expected an error status, got {:?}", status),
+    }
+
+    match StatusKind::Paper.parse(0x1E)? {
+      Status::Paper(paper) => assert!(paper.near_end() && !paper.paper_end()),
+      status => panic!("expected a paper status, got {:?}", status),
+    }
+
+    // invalid fixed bits
+    assert!(StatusKind::Paper.parse(0x00).is_err());
+    assert!(StatusKind::Printer.parse(0x93).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_check() -> Result<()> {
+    assert!(StatusKind::Printer.parse(0x12)?.check().is_ok());
+    assert!(matches!(
+      StatusKind::Printer.parse(0x1A)?.check(),
+      Err(PrinterError::Offline)
+    ));
+    assert!(matches!(
+      StatusKind::Offline.parse(0x16)?.check(),
+      Err(PrinterError::CoverOpen)
+    ));
+    assert!(matches!(
+      StatusKind::Error.parse(0x3A)?.check(),
+      Err(PrinterError::UnrecoverableError)
+    ));
+    assert!(matches!(
+      StatusKind::Error.parse(0x1A)?.check(),
+      Err(PrinterError::CutterError)
+    ));
+    assert!(matches!(
+      StatusKind::Error.parse(0x52)?.check(),
+      Err(PrinterError::RecoverableError)
+    ));
+    // paper near end is not an error
+    assert!(StatusKind::Paper.parse(0x1E)?.check().is_ok());
+    assert!(matches!(
+      StatusKind::Paper.parse(0x72)?.check(),
+      Err(PrinterError::PaperEnd)
+    ));
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_deserialize_from_json() {
+    let kind: super::StatusKind = serde_json::from_str("\"ink_near_end\"").unwrap();
+    assert_eq!(kind, super::StatusKind::InkNearEnd);
+  }
+}
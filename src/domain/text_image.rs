@@ -0,0 +1,495 @@
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use ab_glyph::{point, Font, FontRef, GlyphId, Point, PxScale, ScaleFont};
+use image::{DynamicImage, GrayImage, Luma};
+use rustybuzz::{Direction, Face, UnicodeBuffer};
+use unicode_bidi::{bidi_class, BidiClass, ParagraphBidiInfo};
+use unicode_script::{Script, UnicodeScript};
+
+use crate::domain::{Alignment, Graphic, GraphicDensity, GraphicSize};
+use crate::error::{PrinterError, Result};
+
+/// Text rendered with a TrueType or OpenType font, for typefaces and scripts the printer's
+/// own fonts cannot print
+#[derive(Clone)]
+pub struct TextImage {
+  text: String,
+  font: Arc<Vec<u8>>,
+  size: f32,
+  alignment: Alignment,
+  width: u32,
+}
+
+impl fmt::Display for TextImage {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "text: {}, size: {}, alignment: {}, width: {}",
+      self.text, self.size, self.alignment, self.width
+    )
+  }
+}
+
+impl fmt::Debug for TextImage {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("TextImage")
+      .field("text", &self.text)
+      .field("size", &self.size)
+      .field("alignment", &self.alignment)
+      .field("width", &self.width)
+      .finish_non_exhaustive()
+  }
+}
+
+impl TextImage {
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  /// The height of the font in pixels
+  pub fn size(&self) -> f32 {
+    self.size
+  }
+
+  pub fn alignment(&self) -> &Alignment {
+    &self.alignment
+  }
+
+  /// The width in pixels lines are wrapped at
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  pub fn builder() -> TextImageBuilder {
+    TextImageBuilder::default()
+  }
+
+  /// Returns true if the text contains right-to-left or complex script characters, which need
+  /// bidi reordering and shaping before they can be printed
+  pub fn requires_shaping(text: &str) -> bool {
+    text.chars().any(|character| {
+      matches!(bidi_class(character), BidiClass::R | BidiClass::AL)
+        || !matches!(
+          character.script(),
+          Script::Common | Script::Inherited | Script::Latin | Script::Greek | Script::Cyrillic
+        )
+    })
+  }
+
+  fn face(&self) -> Result<Face<'_>> {
+    Face::from_slice(&self.font, 0).ok_or_else(|| PrinterError::input("Invalid font"))
+  }
+
+  /// Converts font units to pixels
+  fn scale(&self, face: &Face) -> f32 {
+    self.size / f32::from(face.ascender() - face.descender())
+  }
+
+  /// Splits a paragraph into byte ranges that fit the width, breaking at spaces where possible
+  #[allow(clippy::cast_precision_loss)]
+  fn wrap(&self, face: &Face, paragraph: &str) -> Vec<Range<usize>> {
+    let max_width = self.width as f32;
+    let mut lines = Vec::new();
+    let mut line: Option<Range<usize>> = None;
+    let mut offset = 0;
+
+    for word in paragraph.split(' ') {
+      let range = offset..offset + word.len();
+      offset = range.end + 1;
+
+      let candidate = line
+        .as_ref()
+        .map_or(range.clone(), |line| line.start..range.end);
+      if self.measure(face, &paragraph[candidate.clone()]) <= max_width {
+        line = Some(candidate);
+        continue;
+      }
+
+      lines.extend(line.take());
+
+      // words wider than a line are broken between characters
+      let mut start = range.start;
+      for (index, character) in word.char_indices().skip(1) {
+        let end = range.start + index + character.len_utf8();
+        if self.measure(face, &paragraph[start..end]) > max_width {
+          lines.push(start..range.start + index);
+          start = range.start + index;
+        }
+      }
+      line = Some(start..range.end);
+    }
+
+    lines.extend(line);
+    lines
+  }
+
+  /// The width of shaped text in pixels
+  #[allow(clippy::cast_precision_loss)]
+  fn measure(&self, face: &Face, text: &str) -> f32 {
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.guess_segment_properties();
+    let output = rustybuzz::shape(face, &[], buffer);
+
+    let advance: i32 = output
+      .glyph_positions()
+      .iter()
+      .map(|position| position.x_advance)
+      .sum();
+    advance as f32 * self.scale(face)
+  }
+
+  /// Shapes a line of a paragraph, returning its glyphs positioned from left to right relative
+  /// to the start of the baseline, and the width of the line
+  #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+  fn shape(
+    &self,
+    face: &Face,
+    bidi: &ParagraphBidiInfo,
+    line: Range<usize>,
+  ) -> (Vec<(GlyphId, Point)>, f32) {
+    let scale = self.scale(face);
+    let mut glyphs = Vec::new();
+    let mut x = 0.0;
+    if line.is_empty() {
+      return (glyphs, x);
+    }
+
+    let (levels, runs) = bidi.visual_runs(line);
+    for run in runs {
+      let mut buffer = UnicodeBuffer::new();
+      buffer.push_str(&bidi.text[run.clone()]);
+      buffer.set_direction(if levels[run.start].is_rtl() {
+        Direction::RightToLeft
+      } else {
+        Direction::LeftToRight
+      });
+      buffer.guess_segment_properties();
+      let output = rustybuzz::shape(face, &[], buffer);
+
+      for (info, position) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+        let offset = point(
+          x + position.x_offset as f32 * scale,
+          -(position.y_offset as f32) * scale,
+        );
+        glyphs.push((GlyphId(info.glyph_id as u16), offset));
+        x += position.x_advance as f32 * scale;
+      }
+    }
+
+    (glyphs, x)
+  }
+
+  /// Draws the text in black on a white background, after reordering right-to-left runs and
+  /// shaping them with the font
+  pub fn render(&self) -> Result<GrayImage> {
+    self.render_text(&self.text)
+  }
+
+  /// Renders other text with the settings of the text image
+  #[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+  )]
+  pub(crate) fn render_text(&self, text: &str) -> Result<GrayImage> {
+    let font =
+      FontRef::try_from_slice(&self.font).map_err(|_| PrinterError::input("Invalid font"))?;
+    let face = self.face()?;
+    let scaled = font.as_scaled(PxScale::from(self.size));
+    let line_height = (scaled.ascent() - scaled.descent() + scaled.line_gap()).ceil();
+
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+      let bidi = ParagraphBidiInfo::new(paragraph, None);
+      for line in self.wrap(&face, paragraph) {
+        lines.push(self.shape(&face, &bidi, line));
+      }
+    }
+
+    let height = ((lines.len() as f32 * line_height) as u32).max(1);
+    let mut img = GrayImage::from_pixel(self.width, height, Luma([255]));
+
+    for (index, (glyphs, width)) in lines.iter().enumerate() {
+      let free = (self.width as f32 - width).max(0.0);
+      let x = match self.alignment {
+        Alignment::Left => 0.0,
+        Alignment::Center => (free / 2.0).floor(),
+        Alignment::Right => free,
+      };
+      let baseline = index as f32 * line_height + scaled.ascent();
+
+      for (id, offset) in glyphs {
+        let glyph = id.with_scale_and_position(self.size, point(x + offset.x, baseline + offset.y));
+        if let Some(outline) = font.outline_glyph(glyph) {
+          let bounds = outline.px_bounds();
+          outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x + gx as f32;
+            let py = bounds.min.y + gy as f32;
+            if px >= 0.0 && py >= 0.0 && (px as u32) < img.width() && (py as u32) < img.height() {
+              let pixel = img.get_pixel_mut(px as u32, py as u32);
+              let value = 255 - (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+              pixel.0[0] = pixel.0[0].min(value);
+            }
+          });
+        }
+      }
+    }
+
+    Ok(img)
+  }
+
+  /// The rendered text as a graphic the width of the text image
+  pub fn graphic(&self) -> Result<Graphic> {
+    self.text_graphic(&self.text)
+  }
+
+  pub(crate) fn text_graphic(&self, text: &str) -> Result<Graphic> {
+    Ok(Graphic::from_image(
+      DynamicImage::ImageLuma8(self.render_text(text)?),
+      GraphicDensity::Full,
+      self.width,
+      GraphicSize::Normal,
+    ))
+  }
+}
+
+pub struct TextImageBuilder {
+  text: String,
+  font: Option<Vec<u8>>,
+  font_path: Option<String>,
+  size: f32,
+  alignment: Alignment,
+  width: u32,
+}
+
+impl Default for TextImageBuilder {
+  fn default() -> Self {
+    Self {
+      text: String::new(),
+      font: None,
+      font_path: None,
+      size: 24.0,
+      alignment: Alignment::Left,
+      width: 512,
+    }
+  }
+}
+
+impl TextImageBuilder {
+  pub fn text<T: AsRef<str>>(&mut self, text: T) -> &mut Self {
+    self.text = text.as_ref().to_string();
+    self
+  }
+
+  /// The contents of a TTF or OTF font file
+  pub fn font<T: AsRef<[u8]>>(&mut self, font: T) -> &mut Self {
+    self.font = Some(font.as_ref().to_vec());
+    self.font_path = None;
+    self
+  }
+
+  pub fn font_path<T: AsRef<str>>(&mut self, path: T) -> &mut Self {
+    self.font_path = Some(path.as_ref().to_string());
+    self.font = None;
+    self
+  }
+
+  /// The height of the font in pixels
+  pub fn size(&mut self, size: f32) -> &mut Self {
+    self.size = size;
+    self
+  }
+
+  pub fn alignment(&mut self, alignment: Alignment) -> &mut Self {
+    self.alignment = alignment;
+    self
+  }
+
+  /// The width in pixels to wrap lines at, usually the printable width of the paper
+  pub fn width(&mut self, width: u32) -> &mut Self {
+    self.width = width;
+    self
+  }
+
+  pub fn build(self) -> Result<TextImage> {
+    if !(self.size.is_finite() && self.size > 0.0) {
+      return Err(PrinterError::input("Font size must be greater than 0"));
+    }
+    if self.width == 0 {
+      return Err(PrinterError::input("Width must be greater than 0"));
+    }
+
+    let data = match (self.font, self.font_path) {
+      (Some(data), _) => data,
+      (None, Some(path)) => std::fs::read(path)?,
+      (None, None) => return Err(PrinterError::input("No font provided")),
+    };
+    if FontRef::try_from_slice(&data).is_err() || Face::from_slice(&data, 0).is_none() {
+      return Err(PrinterError::input("Invalid font"));
+    }
+
+    Ok(TextImage {
+      text: self.text,
+      font: Arc::new(data),
+      size: self.size,
+      alignment: self.alignment,
+      width: self.width,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const FONT: &str = "resources/fonts/DejaVuSans.ttf";
+
+  fn text_image(text: &str, alignment: Alignment, width: u32) -> Result<TextImage> {
+    let mut builder = TextImage::builder();
+    builder
+      .text(text)
+      .font_path(FONT)
+      .size(20.0)
+      .alignment(alignment)
+      .width(width);
+    builder.build()
+  }
+
+  /// The text of each wrapped line
+  fn lines(text_image: &TextImage) -> Vec<String> {
+    let face = text_image.face().unwrap();
+    text_image
+      .text()
+      .lines()
+      .flat_map(|paragraph| {
+        text_image
+          .wrap(&face, paragraph)
+          .into_iter()
+          .map(|line| paragraph[line].to_string())
+          .collect::<Vec<_>>()
+      })
+      .collect()
+  }
+
+  /// The first and last columns containing dark pixels
+  fn ink(img: &GrayImage) -> (u32, u32) {
+    let columns: Vec<u32> = img
+      .enumerate_pixels()
+      .filter(|(_, _, pixel)| pixel.0[0] < 128)
+      .map(|(x, _, _)| x)
+      .collect();
+    (
+      columns.iter().copied().min().unwrap(),
+      columns.iter().copied().max().unwrap(),
+    )
+  }
+
+  #[test]
+  fn test_render() -> Result<()> {
+    let img = text_image("Hello", Alignment::Left, 200)?.render()?;
+    assert_eq!(img.width(), 200);
+    assert!(img.height() >= 20 && img.height() < 30);
+
+    let (left, right) = ink(&img);
+    assert!(left < 5);
+    assert!(right < 100);
+    Ok(())
+  }
+
+  #[test]
+  fn test_alignment() -> Result<()> {
+    let (left, _) = ink(&text_image("Hello", Alignment::Left, 200)?.render()?);
+    let (center, _) = ink(&text_image("Hello", Alignment::Center, 200)?.render()?);
+    let (_, right) = ink(&text_image("Hello", Alignment::Right, 200)?.render()?);
+
+    assert!(left < center);
+    assert!(center > 50 && center < 100);
+    assert!(right > 190);
+    Ok(())
+  }
+
+  #[test]
+  fn test_wrap() -> Result<()> {
+    let single = text_image("Hello", Alignment::Left, 200)?;
+    let wrapped = text_image("Hello world, this line is too long", Alignment::Left, 200)?;
+    assert_eq!(
+      lines(&wrapped),
+      vec!["Hello world, this line is", "too long"]
+    );
+    assert_eq!(wrapped.render()?.height(), single.render()?.height() * 2);
+
+    let broken = text_image("Supercalifragilistic", Alignment::Left, 60)?;
+    let broken = lines(&broken);
+    assert!(broken.len() > 1);
+    assert_eq!(broken.concat(), "Supercalifragilistic");
+
+    let paragraphs = text_image("Hello\n\nworld", Alignment::Left, 200)?;
+    assert_eq!(lines(&paragraphs), vec!["Hello", "", "world"]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_requires_shaping() {
+    assert!(!TextImage::requires_shaping("Hello, world! 123"));
+    assert!(!TextImage::requires_shaping("Grüße, Привет, Γειά"));
+    assert!(TextImage::requires_shaping("Total: שלום"));
+    assert!(TextImage::requires_shaping("مرحبا"));
+    assert!(TextImage::requires_shaping("नमस्ते"));
+  }
+
+  #[test]
+  fn test_shaping() -> Result<()> {
+    // joined arabic letters are narrower than the same letters in their isolated forms
+    let arabic = text_image("سلام", Alignment::Left, 200)?;
+    let face = arabic.face()?;
+    let isolated: f32 = "سلام"
+      .chars()
+      .map(|character| arabic.measure(&face, &character.to_string()))
+      .sum();
+    assert!(arabic.measure(&face, "سلام") < isolated);
+    Ok(())
+  }
+
+  #[test]
+  fn test_bidi() -> Result<()> {
+    // the first letter of right-to-left text is drawn at the right of the line
+    let hebrew = text_image("אב", Alignment::Left, 200)?;
+    let face = hebrew.face()?;
+    let bidi = ParagraphBidiInfo::new("אב", None);
+    let (glyphs, width) = hebrew.shape(&face, &bidi, 0.."אב".len());
+    let alef = face.glyph_index('א').unwrap().0;
+    let bet = face.glyph_index('ב').unwrap().0;
+    let ids: Vec<u16> = glyphs.iter().map(|(id, _)| id.0).collect();
+    assert_eq!(ids, vec![bet, alef]);
+    assert!(width > 0.0);
+
+    // embedded left-to-right runs keep their order
+    let mixed = "שלום abc";
+    let bidi = ParagraphBidiInfo::new(mixed, None);
+    let (glyphs, _) = hebrew.shape(&face, &bidi, 0..mixed.len());
+    let a = face.glyph_index('a').unwrap().0;
+    let shin = face.glyph_index('ש').unwrap().0;
+    let ids: Vec<u16> = glyphs.iter().map(|(id, _)| id.0).collect();
+    assert_eq!(&ids[..3], &[a, a + 1, a + 2]);
+    assert_eq!(ids.last(), Some(&shin));
+    Ok(())
+  }
+
+  #[test]
+  fn test_invalid() {
+    let mut builder = TextImage::builder();
+    builder.text("Hello");
+    assert!(builder.build().is_err());
+
+    let mut builder = TextImage::builder();
+    builder.text("Hello").font(b"not a font");
+    assert!(builder.build().is_err());
+
+    let mut builder = TextImage::builder();
+    builder.text("Hello").font_path(FONT).size(0.0);
+    assert!(builder.build().is_err());
+  }
+}
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::fmt;
+use std::sync::PoisonError;
 
 #[cfg(feature = "graphics")]
 use image::ImageError;
@@ -10,6 +11,18 @@ pub enum PrinterError {
   Input(String),
   Network(String),
   Configuration(String),
+  /// The printer did not reply in time
+  Timeout(String),
+  Offline,
+  CoverOpen,
+  PaperEnd,
+  /// The autocutter has jammed
+  CutterError,
+  /// An error that clears by itself or with `DLE ENQ`
+  RecoverableError,
+  /// An error that needs the printer to be turned off and on
+  UnrecoverableError,
+  InkEnd,
 }
 
 impl std::error::Error for PrinterError {}
@@ -21,6 +34,14 @@ impl fmt::Display for PrinterError {
       PrinterError::Network(ref err) => write!(f, "Network error: {err}"),
       PrinterError::Configuration(ref err) => write!(f, "Configuration error: {err}"),
       PrinterError::Input(ref err) => write!(f, "Input error: {err}"),
+      PrinterError::Timeout(ref err) => write!(f, "Timeout: {err}"),
+      PrinterError::Offline => write!(f, "The printer is offline"),
+      PrinterError::CoverOpen => write!(f, "The printer cover is open"),
+      PrinterError::PaperEnd => write!(f, "The printer is out of paper"),
+      PrinterError::CutterError => write!(f, "The autocutter has jammed"),
+      PrinterError::RecoverableError => write!(f, "The printer has a recoverable error"),
+      PrinterError::UnrecoverableError => write!(f, "The printer has an unrecoverable error"),
+      PrinterError::InkEnd => write!(f, "The printer is out of ink"),
     }
   }
 }
@@ -42,6 +63,12 @@ impl From<std::io::Error> for PrinterError {
   }
 }
 
+impl<T> From<PoisonError<T>> for PrinterError {
+  fn from(err: PoisonError<T>) -> PrinterError {
+    PrinterError::Io(err.to_string())
+  }
+}
+
 impl From<Cow<'_, str>> for PrinterError {
   fn from(value: Cow<'_, str>) -> Self {
     PrinterError::Io(value.into_owned())
@@ -0,0 +1,224 @@
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::debug;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use crate::error::{PrinterError, Result};
+use crate::io::{ConsoleDriver, NoopDriver};
+
+/// Sends commands to the printer without blocking the async runtime
+#[async_trait]
+pub trait AsyncDriver: Send + Sync {
+  fn name(&self) -> &str;
+
+  async fn write(&self, data: &[u8]) -> Result<()>;
+
+  async fn flush(&self) -> Result<()>;
+
+  /// Reads a reply from the printer into the buffer, returning the number of bytes read. Waits
+  /// at most `timeout` for the reply before returning a `Timeout` error. Drivers that cannot
+  /// read from the printer return a `Configuration` error.
+  async fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    let _ = (buffer, timeout);
+    Err(PrinterError::configuration(format!(
+      "The {} driver cannot read from the printer",
+      self.name()
+    )))
+  }
+}
+
+pub struct AsyncNetworkDriver(Mutex<TcpStream>);
+
+impl AsyncNetworkDriver {
+  pub async fn open<A: AsRef<str>>(host: A, port: u16) -> Result<Box<AsyncNetworkDriver>> {
+    let host = host.as_ref();
+    debug!("Connecting to address {}:{}", host, port);
+    let stream = TcpStream::connect((host, port)).await?;
+    Ok(Box::new(AsyncNetworkDriver(Mutex::new(stream))))
+  }
+}
+
+#[async_trait]
+impl AsyncDriver for AsyncNetworkDriver {
+  fn name(&self) -> &'static str {
+    "network"
+  }
+
+  async fn write(&self, data: &[u8]) -> Result<()> {
+    self
+      .0
+      .lock()
+      .await
+      .write_all(data)
+      .await
+      .map_err(Into::into)
+  }
+
+  async fn flush(&self) -> Result<()> {
+    self.0.lock().await.flush().await.map_err(Into::into)
+  }
+
+  async fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    let mut stream = self.0.lock().await;
+    match tokio::time::timeout(timeout, stream.read(buffer)).await {
+      Ok(Ok(0)) if !buffer.is_empty() => Err(PrinterError::Network(
+        "The printer closed the connection".to_string(),
+      )),
+      Ok(read) => read.map_err(Into::into),
+      Err(_) => Err(PrinterError::Timeout(
+        "No reply from the printer".to_string(),
+      )),
+    }
+  }
+}
+
+pub struct AsyncFileDriver(Mutex<File>);
+
+impl AsyncFileDriver {
+  pub async fn new<P: AsRef<Path>>(path: P) -> Result<Box<Self>> {
+    let file = OpenOptions::new().read(true).write(true).open(path).await?;
+    Ok(Box::new(AsyncFileDriver(Mutex::new(file))))
+  }
+}
+
+#[async_trait]
+impl AsyncDriver for AsyncFileDriver {
+  fn name(&self) -> &'static str {
+    "file"
+  }
+
+  async fn write(&self, data: &[u8]) -> Result<()> {
+    self
+      .0
+      .lock()
+      .await
+      .write_all(data)
+      .await
+      .map_err(Into::into)
+  }
+
+  async fn flush(&self) -> Result<()> {
+    self.0.lock().await.flush().await.map_err(Into::into)
+  }
+}
+
+#[async_trait]
+impl AsyncDriver for ConsoleDriver {
+  fn name(&self) -> &'static str {
+    "console"
+  }
+
+  async fn write(&self, data: &[u8]) -> Result<()> {
+    io::stdout().write_all(data).await.map_err(Into::into)
+  }
+
+  async fn flush(&self) -> Result<()> {
+    Ok(())
+  }
+}
+
+#[async_trait]
+impl AsyncDriver for NoopDriver {
+  fn name(&self) -> &'static str {
+    "noop"
+  }
+
+  async fn write(&self, _data: &[u8]) -> Result<()> {
+    Ok(())
+  }
+
+  async fn flush(&self) -> Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::io::AsyncReadExt;
+  use tokio::net::TcpListener;
+
+  use super::*;
+  use crate::{AsyncPrinter, Protocol, StatusKind};
+
+  #[tokio::test]
+  async fn test_network_driver() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    let server = tokio::spawn(async move {
+      let (mut socket, _) = listener.accept().await?;
+      let mut received = Vec::new();
+      socket.read_to_end(&mut received).await?;
+      Ok::<_, std::io::Error>(received)
+    });
+
+    // printing is spawned to check the printer can be moved between worker threads
+    let client = tokio::spawn(async move {
+      let driver = AsyncNetworkDriver::open("127.0.0.1", port).await?;
+      let mut printer = AsyncPrinter::open(driver)?;
+      printer.init()?.text("Hello")?.cut()?;
+      printer.flush().await
+    });
+    client.await.unwrap()?;
+
+    let mut protocol = Protocol::default();
+    let expected = [
+      protocol.init().to_vec(),
+      protocol.println("Hello")?,
+      protocol.cut(false)?,
+    ]
+    .concat();
+    assert_eq!(server.await.unwrap()?, expected);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_network_status() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    let server = tokio::spawn(async move {
+      let (mut socket, _) = listener.accept().await?;
+      let mut received = vec![0; 5];
+      socket.read_exact(&mut received).await?;
+      socket.write_all(&[0x16]).await?;
+      Ok::<_, std::io::Error>(received)
+    });
+
+    let mut printer = AsyncPrinter::open(AsyncNetworkDriver::open("127.0.0.1", port).await?)?;
+    let status = printer.init()?.status(StatusKind::Printer).await?;
+    assert_eq!(status, StatusKind::Printer.parse(0x16)?);
+
+    let mut protocol = Protocol::default();
+    let expected = [
+      protocol.init().to_vec(),
+      protocol.status(&StatusKind::Printer)?,
+    ]
+    .concat();
+    assert_eq!(server.await.unwrap()?, expected);
+
+    // drivers that cannot read from the printer cannot request a status
+    let mut printer = AsyncPrinter::open(Box::new(NoopDriver))?;
+    assert!(printer.status(StatusKind::Printer).await.is_err());
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_file_driver() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("recibo-async-driver-{}", std::process::id()));
+    File::create(&path).await?;
+
+    let mut printer = AsyncPrinter::open(AsyncFileDriver::new(&path).await?)?;
+    printer.write(b"Hello")?.feed(1)?;
+    // nothing is sent until the printer is flushed
+    assert!(tokio::fs::read(&path).await?.is_empty());
+    printer.flush().await?;
+
+    assert_eq!(tokio::fs::read(&path).await?, b"Hello\x1b\x64\x01");
+    tokio::fs::remove_file(path).await?;
+    Ok(())
+  }
+}
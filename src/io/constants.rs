@@ -1,7 +1,11 @@
 pub const EOL: &str = "\n";
 pub const GS: u8 = 0x1D;
 pub const ESC: u8 = 0x1B;
+pub const FS: u8 = 0x1C;
 pub const NIL: u8 = 0x00;
+pub const DLE: u8 = 0x10;
+pub const EOT: u8 = 0x04;
+pub const ACK: u8 = 0x06;
 
 pub const HARDWARE_INIT: &[u8] = &[ESC, 0x40];
 pub const HARDWARE_SELECT: &[u8] = &[ESC, 0x3D, 0x01];
@@ -35,8 +39,8 @@ pub const TEXT_JUSTIFY_CENTER: &[u8] = &[ESC, 0x61, 0x01];
 pub const TEXT_JUSTIFY_RIGHT: &[u8] = &[ESC, 0x61, 0x02];
 pub const TEXT_MARGIN_LEFT: &[u8] = &[GS, 0x4C];
 pub const TEXT_PRINTABLE_AREA: &[u8] = &[GS, 0x57];
-pub const TEXT_LINESPACING: &[u8] = &[ESC, 0x03];
-pub const TEXT_RESET_LINESPACING: &[u8] = &[ESC, 0x02];
+pub const TEXT_LINESPACING: &[u8] = &[ESC, 0x33];
+pub const TEXT_RESET_LINESPACING: &[u8] = &[ESC, 0x32];
 pub const TEXT_SIZE_SELECT: &[u8] = &[GS, 0x21];
 // Font selection
 pub const FONT_A: &[u8] = &[ESC, 0x4D, 0x00];
@@ -48,6 +52,16 @@ pub const BARCODE_HEIGHT: &[u8] = &[GS, 0x68];
 pub const BARCODE_WIDTH: &[u8] = &[GS, 0x77];
 pub const BARCODE_TEXT_POSITION: &[u8] = &[GS, 0x48];
 
+pub const BARCODE_WIDTH_MIN: u8 = 2;
+pub const BARCODE_WIDTH_MAX: u8 = 6;
+// rasterised barcodes can use any module width the paper fits
+pub const BARCODE_RASTER_WIDTH_MIN: u8 = 1;
+pub const BARCODE_RASTER_WIDTH_MAX: u8 = 6;
+// modules of white space on either side of a rasterised barcode
+pub const BARCODE_RASTER_QUIET_ZONE: u32 = 10;
+// the printable width of 80 mm paper, which rasterised QR codes are scaled down to fit
+pub const QR_RASTER_WIDTH_MAX: u32 = 512;
+
 pub const BARCODE_FONT_A: &[u8] = &[GS, 0x66, 0x00];
 pub const BARCODE_FONT_B: &[u8] = &[GS, 0x66, 0x01];
 
@@ -64,6 +78,28 @@ pub const BARCODE_TYPE_CODE39: &[u8] = &[GS, 0x6B, 0x04];
 pub const BARCODE_TYPE_ITF: &[u8] = &[GS, 0x6B, 0x05];
 pub const BARCODE_TYPE_CODABAR: &[u8] = &[GS, 0x6B, 0x06];
 
+// Barcode types from this value onwards use function B (length prefixed data)
+pub const BARCODE_FUNCTION_B: u8 = 0x41;
+
+pub const BARCODE_TYPE_CODE128: &[u8] = &[GS, 0x6B, 0x49];
+pub const BARCODE_TYPE_CODE93: &[u8] = &[GS, 0x6B, 0x48];
+pub const BARCODE_TYPE_GS1_128: &[u8] = &[GS, 0x6B, 0x4A];
+// linear GS1 DataBar, the stacked variants use GS ( k instead
+pub const BARCODE_TYPE_GS1_DATABAR_OMNIDIRECTIONAL: &[u8] = &[GS, 0x6B, 0x4B];
+pub const BARCODE_TYPE_GS1_DATABAR_TRUNCATED: &[u8] = &[GS, 0x6B, 0x4C];
+pub const BARCODE_TYPE_GS1_DATABAR_LIMITED: &[u8] = &[GS, 0x6B, 0x4D];
+pub const BARCODE_TYPE_GS1_DATABAR_EXPANDED: &[u8] = &[GS, 0x6B, 0x4E];
+
+pub const CODE128_CODE_SET_A: &[u8] = b"{A";
+pub const CODE128_CODE_SET_B: &[u8] = b"{B";
+pub const CODE128_CODE_SET_C: &[u8] = b"{C";
+pub const CODE128_FNC1: &[u8] = b"{1";
+pub const CODE128_FNC2: &[u8] = b"{2";
+pub const CODE128_FNC3: &[u8] = b"{3";
+pub const CODE128_FNC4: &[u8] = b"{4";
+pub const CODE128_SHIFT: &[u8] = b"{S";
+pub const CODE128_BRACE: &[u8] = b"{{";
+
 // QR Code commands
 
 pub const QR_CORRECTION_ERROR_LEVEL_LOW: u8 = 0x30;
@@ -81,6 +117,95 @@ pub const QR_DATA_STORE_PREFIX: &[u8] = &[GS, 0x28, 0x6B];
 pub const QR_DATA_STORE_SUFFIX: &[u8] = &[0x31, 0x50, 0x30];
 pub const QR_PRINT: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x51, 0x30];
 
+// PDF417 commands
+
+pub const PDF417_COLUMNS: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x30, 0x41];
+pub const PDF417_ROWS: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x30, 0x42];
+pub const PDF417_MODULE_WIDTH: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x30, 0x43];
+pub const PDF417_ROW_HEIGHT: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x30, 0x44];
+pub const PDF417_CORRECTION_ERROR_LEVEL: &[u8] = &[GS, 0x28, 0x6B, 0x04, 0x00, 0x30, 0x45, 0x30];
+pub const PDF417_OPTIONS: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x30, 0x46];
+pub const PDF417_DATA_STORE_PREFIX: &[u8] = &[GS, 0x28, 0x6B];
+pub const PDF417_DATA_STORE_SUFFIX: &[u8] = &[0x30, 0x50, 0x30];
+pub const PDF417_PRINT: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x30, 0x51, 0x30];
+
+pub const PDF417_STANDARD: u8 = 0x00;
+pub const PDF417_TRUNCATED: u8 = 0x01;
+
+// DataMatrix commands
+
+pub const DATA_MATRIX_SYMBOL: &[u8] = &[GS, 0x28, 0x6B, 0x05, 0x00, 0x36, 0x42];
+pub const DATA_MATRIX_SIZE: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x36, 0x43];
+pub const DATA_MATRIX_DATA_STORE_PREFIX: &[u8] = &[GS, 0x28, 0x6B];
+pub const DATA_MATRIX_DATA_STORE_SUFFIX: &[u8] = &[0x36, 0x50, 0x30];
+pub const DATA_MATRIX_PRINT: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x36, 0x51, 0x30];
+
+pub const DATA_MATRIX_SQUARE_SIZES: &[u8] = &[
+  10, 12, 14, 16, 18, 20, 22, 24, 26, 32, 36, 40, 44, 48, 52, 64, 72, 80, 88, 96, 104, 120, 132,
+  144,
+];
+pub const DATA_MATRIX_RECTANGLE_ROWS: &[u8] = &[8, 12, 16];
+pub const DATA_MATRIX_RECTANGLE_COLUMNS: &[u8] = &[18, 26, 32, 36, 48];
+
+// Aztec commands
+
+pub const AZTEC_MODE: &[u8] = &[GS, 0x28, 0x6B, 0x04, 0x00, 0x35, 0x42];
+pub const AZTEC_SIZE: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x35, 0x43];
+pub const AZTEC_CORRECTION_ERROR_LEVEL: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x35, 0x45];
+pub const AZTEC_DATA_STORE_PREFIX: &[u8] = &[GS, 0x28, 0x6B];
+pub const AZTEC_DATA_STORE_SUFFIX: &[u8] = &[0x35, 0x50, 0x30];
+pub const AZTEC_PRINT: &[u8] = &[GS, 0x28, 0x6B, 0x03, 0x00, 0x35, 0x51, 0x30];
+
 // Image
 
 pub const IMAGE_HEADER: &[u8] = &[GS, 0x76, 0x30];
+
+pub const IMAGE_COLUMN: &[u8] = &[ESC, 0x2A];
+pub const IMAGE_COLUMN_8_SINGLE_DENSITY: u8 = 0x00;
+pub const IMAGE_COLUMN_8_DOUBLE_DENSITY: u8 = 0x01;
+pub const IMAGE_COLUMN_24_SINGLE_DENSITY: u8 = 0x20;
+pub const IMAGE_COLUMN_24_DOUBLE_DENSITY: u8 = 0x21;
+// 8-dot columns are printed at a third of the vertical resolution, so both
+// column heights advance the paper by 24 motion units
+pub const IMAGE_COLUMN_LINESPACING: u8 = 24;
+
+pub const GRAPHICS_STORE: &[u8] = &[GS, 0x28, 0x4C];
+pub const GRAPHICS_STORE_LARGE: &[u8] = &[GS, 0x38, 0x4C];
+pub const GRAPHICS_RASTER_STORE: &[u8] = &[0x30, 0x70, 0x30];
+pub const GRAPHICS_COLOUR_1: u8 = 0x31;
+pub const GRAPHICS_PRINT: &[u8] = &[GS, 0x28, 0x4C, 0x02, 0x00, 0x30, 0x32];
+
+// NV graphics
+pub const NV_GRAPHICS_KEY_MIN: u8 = 0x20;
+pub const NV_GRAPHICS_KEY_MAX: u8 = 0x7E;
+pub const NV_GRAPHICS_HEIGHT_MAX: u32 = 2304;
+pub const NV_GRAPHICS_WIDTH_MAX: u32 = 8192;
+pub const NV_GRAPHICS_DEFINE: &[u8] = &[0x30, 0x43, 0x30];
+pub const NV_GRAPHICS_PRINT: &[u8] = &[GS, 0x28, 0x4C, 0x06, 0x00, 0x30, 0x45];
+pub const NV_GRAPHICS_DELETE: &[u8] = &[GS, 0x28, 0x4C, 0x04, 0x00, 0x30, 0x42];
+pub const NV_GRAPHICS_DELETE_ALL: &[u8] =
+  &[GS, 0x28, 0x4C, 0x05, 0x00, 0x30, 0x41, 0x43, 0x4C, 0x52];
+pub const NV_GRAPHICS_KEY_LIST: &[u8] = &[GS, 0x28, 0x4C, 0x04, 0x00, 0x30, 0x40, 0x4B, 0x43];
+pub const NV_GRAPHICS_REMAINING_CAPACITY: &[u8] = &[GS, 0x28, 0x4C, 0x02, 0x00, 0x30, 0x33];
+pub const NV_GRAPHICS_RESPONSE_HEADER: u8 = 0x37;
+pub const NV_GRAPHICS_RESPONSE_REMAINING_CAPACITY: u8 = 0x31;
+pub const NV_GRAPHICS_RESPONSE_KEY_LIST: u8 = 0x72;
+pub const NV_GRAPHICS_RESPONSE_MORE: u8 = 0x41;
+// acknowledges a block of the key list so the printer sends the next one
+pub const NV_GRAPHICS_KEY_LIST_NEXT: &[u8] = &[ACK];
+
+// Legacy NV and download bit images, stored in 8-dot columns
+pub const NV_BIT_IMAGE_DEFINE: &[u8] = &[FS, 0x71];
+pub const NV_BIT_IMAGE_PRINT: &[u8] = &[FS, 0x70];
+pub const NV_BIT_IMAGE_WIDTH_MAX: u32 = 1023;
+pub const NV_BIT_IMAGE_HEIGHT_MAX: u32 = 288;
+pub const DOWNLOAD_BIT_IMAGE_DEFINE: &[u8] = &[GS, 0x2A];
+pub const DOWNLOAD_BIT_IMAGE_PRINT: &[u8] = &[GS, 0x2F];
+// the width and height are counted in blocks of 8 by 8 dots, each sent as 8 bytes
+pub const DOWNLOAD_BIT_IMAGE_WIDTH_MAX: u32 = 255;
+pub const DOWNLOAD_BIT_IMAGE_HEIGHT_MAX: u32 = 48;
+pub const DOWNLOAD_BIT_IMAGE_BLOCKS_MAX: u32 = 1536;
+
+// Real-time status
+pub const STATUS_REALTIME: &[u8] = &[DLE, EOT];
+pub const STATUS_AUTOMATIC: &[u8] = &[GS, 0x61];
@@ -1,24 +1,50 @@
-use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::fs::{File, OpenOptions};
 use std::io;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::path::Path;
-use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
 use log::debug;
 
-use crate::error::Result;
+use crate::error::{PrinterError, Result};
 
-pub trait Driver {
+/// Sends commands to the printer. Drivers can be shared between threads, so a printer can be
+/// used from multi-threaded runtimes.
+pub trait Driver: Send + Sync {
   fn name(&self) -> &str;
 
   fn write(&self, data: &[u8]) -> Result<()>;
 
   fn flush(&self) -> Result<()>;
+
+  /// Reads a reply from the printer into the buffer, returning the number of bytes read. Waits
+  /// at most `timeout` for the reply before returning a `Timeout` error. Drivers that cannot
+  /// read from the printer return a `Configuration` error.
+  fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    let _ = (buffer, timeout);
+    Err(PrinterError::configuration(format!(
+      "The {} driver cannot read from the printer",
+      self.name()
+    )))
+  }
+
+  /// Whether the driver can read replies from the printer
+  fn bidirectional(&self) -> bool {
+    false
+  }
 }
 
-pub struct NetworkDriver(Rc<RefCell<TcpStream>>);
+pub struct NetworkDriver {
+  stream: Mutex<TcpStream>,
+  // a separate handle, so waiting for a reply does not block writes
+  reader: Mutex<TcpStream>,
+}
 
 #[derive(Default)]
 pub struct ConsoleDriver {}
@@ -48,8 +74,11 @@ impl NetworkDriver {
     fn inner(host: &str, port: u16) -> Result<Box<NetworkDriver>> {
       debug!("Connecting to address {}:{}", host, port);
       let stream = TcpStream::connect((host, port))?;
-      let inner = Rc::new(RefCell::new(stream));
-      Ok(Box::new(NetworkDriver(inner)))
+      let reader = stream.try_clone()?;
+      Ok(Box::new(NetworkDriver {
+        stream: Mutex::new(stream),
+        reader: Mutex::new(reader),
+      }))
     }
     inner(host.as_ref(), port)
   }
@@ -61,21 +90,42 @@ impl Driver for NetworkDriver {
   }
 
   fn write(&self, data: &[u8]) -> Result<()> {
-    self.0.borrow_mut().write_all(data).map_err(Into::into)
+    self.stream.lock()?.write_all(data).map_err(Into::into)
   }
 
   fn flush(&self) -> Result<()> {
-    self.0.borrow_mut().flush().map_err(Into::into)
+    self.stream.lock()?.flush().map_err(Into::into)
+  }
+
+  fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    let mut reader = self.reader.lock()?;
+    reader.set_read_timeout(Some(timeout))?;
+    match reader.read(buffer).map_err(read_error)? {
+      0 if !buffer.is_empty() => Err(PrinterError::Network(
+        "The printer closed the connection".to_string(),
+      )),
+      read => Ok(read),
+    }
+  }
+
+  fn bidirectional(&self) -> bool {
+    true
   }
 }
 
-pub struct FileDriver(Rc<RefCell<File>>);
+pub struct FileDriver {
+  file: Mutex<File>,
+  // started by the first read, as files have no read timeout
+  reader: Mutex<Option<ReadThread>>,
+}
 
 impl FileDriver {
   pub fn new<P: AsRef<Path>>(path: P) -> Result<Box<Self>> {
     let file = OpenOptions::new().read(true).write(true).open(path)?;
-    let file = Rc::new(RefCell::new(file));
-    Ok(Box::new(FileDriver(file)))
+    Ok(Box::new(FileDriver {
+      file: Mutex::new(file),
+      reader: Mutex::new(None),
+    }))
   }
 }
 
@@ -85,11 +135,189 @@ impl Driver for FileDriver {
   }
 
   fn write(&self, data: &[u8]) -> Result<()> {
-    self.0.borrow_mut().write_all(data).map_err(Into::into)
+    self.file.lock()?.write_all(data).map_err(Into::into)
+  }
+
+  fn flush(&self) -> Result<()> {
+    self.file.lock()?.flush().map_err(Into::into)
+  }
+
+  fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    let mut reader = self.reader.lock()?;
+    let thread = match reader.as_mut() {
+      Some(thread) => thread,
+      None => reader.insert(ReadThread::spawn(self.file.lock()?.try_clone()?)),
+    };
+    let result = thread.read(buffer, timeout);
+    // after the end of the file or an error, the next read starts a new thread
+    if matches!(result, Err(PrinterError::Io(_))) {
+      *reader = None;
+    }
+    result
+  }
+
+  fn bidirectional(&self) -> bool {
+    true
+  }
+}
+
+/// Converts an error from reading a reply, where a read that timed out means no reply
+fn read_error(err: io::Error) -> PrinterError {
+  match err.kind() {
+    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => PrinterError::Timeout(err.to_string()),
+    _ => err.into(),
+  }
+}
+
+/// Reads from a handle on a background thread, so reads can time out. The thread stops at the
+/// end of the file, on errors, or after the next read once it is dropped, as a blocked read
+/// cannot be interrupted.
+struct ReadThread {
+  receiver: Receiver<io::Result<Vec<u8>>>,
+  pending: VecDeque<u8>,
+  stopped: Arc<AtomicBool>,
+  handle: Option<JoinHandle<()>>,
+}
+
+impl ReadThread {
+  fn spawn<R: Read + Send + 'static>(mut handle: R) -> Self {
+    let (sender, receiver) = mpsc::channel();
+    let stopped = Arc::new(AtomicBool::new(false));
+    let thread = {
+      let stopped = Arc::clone(&stopped);
+      thread::spawn(move || {
+        let mut buffer = [0; 256];
+        while !stopped.load(Ordering::Relaxed) {
+          let result = handle.read(&mut buffer).map(|read| buffer[..read].to_vec());
+          // an empty read is the end of the file
+          let done = !matches!(&result, Ok(data) if !data.is_empty());
+          if sender.send(result).is_err() || done {
+            break;
+          }
+        }
+        stopped.store(true, Ordering::Relaxed);
+      })
+    };
+    Self {
+      receiver,
+      pending: VecDeque::new(),
+      stopped,
+      handle: Some(thread),
+    }
+  }
+
+  fn read(&mut self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    if self.pending.is_empty() {
+      let closed = || PrinterError::Io("The printer closed the file".to_string());
+      match self.receiver.recv_timeout(timeout) {
+        Ok(Ok(data)) if data.is_empty() => return Err(closed()),
+        Ok(Ok(data)) => self.pending.extend(data),
+        Ok(Err(err)) => return Err(read_error(err)),
+        Err(RecvTimeoutError::Timeout) => {
+          return Err(PrinterError::Timeout(
+            "No reply from the printer".to_string(),
+          ))
+        }
+        Err(RecvTimeoutError::Disconnected) => return Err(closed()),
+      }
+    }
+    let read = self.pending.len().min(buffer.len());
+    for (byte, pending) in buffer.iter_mut().zip(self.pending.drain(..read)) {
+      *byte = pending;
+    }
+    Ok(read)
+  }
+}
+
+impl Drop for ReadThread {
+  fn drop(&mut self) {
+    // a thread still blocked in a read stops once the read returns
+    if self.stopped.swap(true, Ordering::Relaxed) {
+      if let Some(handle) = self.handle.take() {
+        let _ = handle.join();
+      }
+    }
+  }
+}
+
+/// A driver for tests that records what is written and replies with queued responses. Clones
+/// share the same state, so a clone can be inspected after the driver is given to a printer.
+#[derive(Clone, Default)]
+pub struct MockDriver(Arc<Mock>);
+
+#[derive(Default)]
+struct Mock {
+  state: Mutex<MockState>,
+  replied: Condvar,
+}
+
+#[derive(Default)]
+struct MockState {
+  written: Vec<u8>,
+  replies: VecDeque<Vec<u8>>,
+}
+
+impl MockDriver {
+  pub fn new() -> Box<Self> {
+    Box::default()
+  }
+
+  /// Queues a reply, returned by a later read
+  pub fn reply<T: AsRef<[u8]>>(&self, reply: T) -> Result<()> {
+    self
+      .0
+      .state
+      .lock()?
+      .replies
+      .push_back(reply.as_ref().to_vec());
+    self.0.replied.notify_all();
+    Ok(())
+  }
+
+  /// Everything written to the driver
+  pub fn written(&self) -> Result<Vec<u8>> {
+    Ok(self.0.state.lock()?.written.clone())
+  }
+}
+
+impl Driver for MockDriver {
+  fn name(&self) -> &'static str {
+    "mock"
+  }
+
+  fn write(&self, data: &[u8]) -> Result<()> {
+    self.0.state.lock()?.written.extend_from_slice(data);
+    Ok(())
   }
 
   fn flush(&self) -> Result<()> {
-    self.0.borrow_mut().flush().map_err(Into::into)
+    Ok(())
+  }
+
+  /// Returns the next queued reply, waiting for one to be queued until the timeout. A reply
+  /// longer than the buffer is returned over several reads.
+  fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    let (mut state, _) =
+      self
+        .0
+        .replied
+        .wait_timeout_while(self.0.state.lock()?, timeout, |state| {
+          state.replies.is_empty()
+        })?;
+    let mut reply = state
+      .replies
+      .pop_front()
+      .ok_or_else(|| PrinterError::Timeout("No reply from the printer".to_string()))?;
+    let read = reply.len().min(buffer.len());
+    buffer[..read].copy_from_slice(&reply[..read]);
+    if read < reply.len() {
+      state.replies.push_front(reply.split_off(read));
+    }
+    Ok(read)
+  }
+
+  fn bidirectional(&self) -> bool {
+    true
   }
 }
 
@@ -109,3 +337,127 @@ impl Driver for NoopDriver {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+  use std::thread;
+
+  use super::*;
+  use crate::Printer;
+
+  fn assert_send_sync<T: Send + Sync>() {}
+
+  #[test]
+  fn test_send_sync() {
+    assert_send_sync::<NetworkDriver>();
+    assert_send_sync::<FileDriver>();
+    assert_send_sync::<Box<dyn Driver>>();
+    assert_send_sync::<Printer>();
+  }
+
+  #[test]
+  fn test_network_driver_read() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    let server = thread::spawn(move || -> io::Result<()> {
+      let (mut socket, _) = listener.accept()?;
+      let mut request = [0; 3];
+      socket.read_exact(&mut request)?;
+      assert_eq!(&request, &[0x10, 0x04, 0x01]);
+      socket.write_all(&[0x16])?;
+      // wait for the client to time out before closing the connection
+      socket.read_exact(&mut request[..1])
+    });
+
+    let driver = NetworkDriver::open("127.0.0.1", port)?;
+    driver.write(&[0x10, 0x04, 0x01])?;
+    let mut buffer = [0; 4];
+    assert_eq!(driver.read(&mut buffer, Duration::from_secs(5))?, 1);
+    assert_eq!(buffer[0], 0x16);
+    assert!(matches!(
+      driver.read(&mut buffer, Duration::from_millis(50)),
+      Err(PrinterError::Timeout(_))
+    ));
+
+    driver.write(&[0x00])?;
+    server.join().unwrap()?;
+    assert!(matches!(
+      driver.read(&mut buffer, Duration::from_secs(5)),
+      Err(PrinterError::Network(_))
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_file_driver_read() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("recibo-driver-read-{}", std::process::id()));
+    std::fs::write(&path, [0x12, 0x34, 0x56])?;
+
+    let driver = FileDriver::new(&path)?;
+    let mut buffer = [0; 2];
+    assert_eq!(driver.read(&mut buffer, Duration::from_secs(5))?, 2);
+    assert_eq!(buffer, [0x12, 0x34]);
+    assert_eq!(driver.read(&mut buffer, Duration::from_secs(5))?, 1);
+    assert_eq!(buffer[0], 0x56);
+    // the end of the file is an error, every time it is read
+    for _ in 0..2 {
+      assert!(matches!(
+        driver.read(&mut buffer, Duration::from_secs(5)),
+        Err(PrinterError::Io(_))
+      ));
+    }
+
+    std::fs::remove_file(path)?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_mock_driver() -> Result<()> {
+    let driver = MockDriver::default();
+    let mut printer = Printer::builder().driver(Box::new(driver.clone())).build();
+    driver.reply([0x01, 0x02, 0x03])?;
+
+    printer.write(&[0x10, 0x04, 0x01])?;
+    assert_eq!(driver.written()?, vec![0x10, 0x04, 0x01]);
+
+    let mut buffer = [0; 2];
+    assert_eq!(printer.read(&mut buffer, Duration::from_secs(1))?, 2);
+    assert_eq!(buffer, [0x01, 0x02]);
+    assert_eq!(printer.read(&mut buffer, Duration::from_secs(1))?, 1);
+    assert_eq!(buffer[0], 0x03);
+    assert!(matches!(
+      printer.read(&mut buffer, Duration::from_secs(1)),
+      Err(PrinterError::Timeout(_))
+    ));
+
+    let mut printer = Printer::open(Box::new(NoopDriver))?;
+    assert!(matches!(
+      printer.read(&mut buffer, Duration::from_secs(1)),
+      Err(PrinterError::Configuration(_))
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_file_driver_threads() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("recibo-driver-{}", std::process::id()));
+    File::create(&path)?;
+
+    let driver: Arc<FileDriver> = Arc::from(FileDriver::new(&path)?);
+    let handles: Vec<_> = (0..4)
+      .map(|_| {
+        let driver = Arc::clone(&driver);
+        thread::spawn(move || driver.write(b"ab"))
+      })
+      .collect();
+    for handle in handles {
+      handle.join().unwrap()?;
+    }
+    driver.flush()?;
+
+    assert_eq!(std::fs::read(&path)?, b"abababab");
+    std::fs::remove_file(path)?;
+    Ok(())
+  }
+}
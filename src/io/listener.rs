@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use log::debug;
+
+use crate::domain::{AsbStatus, PrinterEvent};
+use crate::error::{PrinterError, Result};
+use crate::io::Driver;
+
+// how often the listener checks whether it has been stopped
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reads Automatic Status Back (ASB) messages from the printer on a background thread and
+/// passes the changes they report to a callback. The listener stops when it is dropped.
+pub struct AsbListener {
+  stopped: Arc<AtomicBool>,
+  handle: Option<JoinHandle<()>>,
+}
+
+impl AsbListener {
+  /// Starts reading from the driver. Everything read that is not an ASB message, such as the
+  /// replies to status requests, is passed on to `replies`.
+  pub(crate) fn spawn<F>(
+    driver: Arc<dyn Driver>,
+    replies: Arc<Replies>,
+    callback: F,
+  ) -> Result<Self>
+  where
+    F: Fn(PrinterEvent) + Send + 'static,
+  {
+    if !driver.bidirectional() {
+      return Err(PrinterError::configuration(format!(
+        "The {} driver cannot read from the printer",
+        driver.name()
+      )));
+    }
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let handle = {
+      let stopped = Arc::clone(&stopped);
+      thread::spawn(move || Self::listen(driver.as_ref(), &replies, &stopped, callback))
+    };
+    Ok(Self {
+      stopped,
+      handle: Some(handle),
+    })
+  }
+
+  fn listen<F>(driver: &dyn Driver, replies: &Replies, stopped: &AtomicBool, callback: F)
+  where
+    F: Fn(PrinterEvent),
+  {
+    let mut status = AsbStatus::default();
+    let mut received = Vec::new();
+    let mut buffer = [0; 64];
+
+    while !stopped.load(Ordering::Relaxed) {
+      match driver.read(&mut buffer, POLL_INTERVAL) {
+        Ok(0) => break,
+        Ok(read) => received.extend_from_slice(&buffer[..read]),
+        Err(PrinterError::Timeout(_)) => {
+          // the start of a message that was not completed in time is part of a reply
+          replies.push(&received);
+          received.clear();
+          continue;
+        }
+        Err(err) => {
+          debug!("Stopping ASB listener: {}", err);
+          break;
+        }
+      }
+
+      while let Some(&first) = received.first() {
+        if !AsbStatus::is_header(first) {
+          // replies are passed on up to the next byte that can start a message
+          let end = received
+            .iter()
+            .position(|byte| AsbStatus::is_header(*byte))
+            .unwrap_or(received.len());
+          replies.push(&received[..end]);
+          received.drain(..end);
+        } else if received.len() < 4 {
+          // waits for the rest of the message
+          break;
+        } else if AsbStatus::is_message(&received[..4]) {
+          let message = [received[0], received[1], received[2], received[3]];
+          received.drain(..4);
+          if let Ok(next) = AsbStatus::parse(message) {
+            for event in next.events(&status) {
+              debug!("Printer event: {}", event);
+              callback(event);
+            }
+            status = next;
+          }
+        } else {
+          replies.push(&received[..1]);
+          received.remove(0);
+        }
+      }
+    }
+  }
+
+  /// Stops listening, waiting for the background thread to finish
+  pub fn stop(mut self) {
+    self.shutdown();
+  }
+
+  fn shutdown(&mut self) {
+    self.stopped.store(true, Ordering::Relaxed);
+    if let Some(handle) = self.handle.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+impl Drop for AsbListener {
+  fn drop(&mut self) {
+    self.shutdown();
+  }
+}
+
+/// The replies read by a listener that are not ASB messages. While a listener runs, the
+/// printer reads its replies from here instead of from the driver.
+#[derive(Default)]
+pub(crate) struct Replies {
+  bytes: Mutex<VecDeque<u8>>,
+  received: Condvar,
+}
+
+impl Replies {
+  fn push(&self, bytes: &[u8]) {
+    if bytes.is_empty() {
+      return;
+    }
+    if let Ok(mut queued) = self.bytes.lock() {
+      queued.extend(bytes);
+      self.received.notify_all();
+    }
+  }
+
+  /// Reads the replies passed on by the listener, waiting at most `timeout` for one
+  pub(crate) fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    let (mut bytes, _) =
+      self
+        .received
+        .wait_timeout_while(self.bytes.lock()?, timeout, |bytes| bytes.is_empty())?;
+    if bytes.is_empty() {
+      return Err(PrinterError::Timeout(
+        "No reply from the printer".to_string(),
+      ));
+    }
+    let read = bytes.len().min(buffer.len());
+    for (byte, reply) in buffer.iter_mut().zip(bytes.drain(..read)) {
+      *byte = reply;
+    }
+    Ok(read)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::mpsc;
+
+  use super::*;
+  use crate::io::{MockDriver, NoopDriver};
+  use crate::{AsbReport, Printer, StatusKind};
+
+  #[test]
+  fn test_listen() -> Result<()> {
+    let driver = MockDriver::default();
+    let mut printer = Printer::open(Box::new(driver.clone()))?;
+    printer.asb(&[AsbReport::Paper, AsbReport::Online])?;
+    assert_eq!(driver.written()?, vec![0x1D, 0x61, 0x0A]);
+
+    let (sender, receiver) = mpsc::channel();
+    let listener = printer.listen(move |event| {
+      let _ = sender.send(event);
+    })?;
+    assert!(printer.listen(|_| {}).is_err());
+
+    // a status reply between messages, and a message split across reads
+    driver.reply([0x10, 0x00, 0x03, 0x00])?;
+    driver.reply([0x16, 0x38, 0x00])?;
+    driver.reply([0x0F, 0x00])?;
+
+    let timeout = Duration::from_secs(2);
+    let events: Vec<_> = (0..4)
+      .map(|_| receiver.recv_timeout(timeout))
+      .collect::<std::result::Result<_, _>>()
+      .unwrap();
+    assert_eq!(
+      events,
+      vec![
+        PrinterEvent::PaperNearEnd,
+        PrinterEvent::PaperEnd,
+        PrinterEvent::CoverOpened,
+        PrinterEvent::Offline
+      ]
+    );
+    assert_eq!(
+      printer.status(StatusKind::Printer)?,
+      StatusKind::Printer.parse(0x16)?
+    );
+
+    // a reply that looks like the start of a message is passed on once no more bytes arrive
+    driver.reply([0x30])?;
+    let mut buffer = [0; 4];
+    assert_eq!(printer.read(&mut buffer, timeout)?, 1);
+    assert_eq!(buffer[0], 0x30);
+
+    // the printer reads from the driver again once the listener stops
+    listener.stop();
+    driver.reply([0x12])?;
+    assert_eq!(
+      printer.status(StatusKind::Printer)?,
+      StatusKind::Printer.parse(0x12)?
+    );
+
+    let mut printer = Printer::open(Box::new(NoopDriver))?;
+    assert!(printer.listen(|_| {}).is_err());
+    Ok(())
+  }
+}
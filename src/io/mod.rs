@@ -1,9 +1,16 @@
+#[cfg(feature = "tokio")]
+mod async_driver;
 pub(crate) mod constants;
 mod driver;
 mod encoder;
+mod listener;
 mod protocol;
+mod symbology;
 
+#[cfg(feature = "tokio")]
+pub use async_driver::*;
 pub use constants::*;
 pub use driver::*;
 pub use encoder::*;
+pub use listener::*;
 pub use protocol::*;
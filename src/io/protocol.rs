@@ -1,28 +1,60 @@
 use std::io::Write;
 
-use barcoders::sym::codabar::Codabar;
-use barcoders::sym::code39::Code39;
-use barcoders::sym::ean13::{EAN13, UPCA};
-use barcoders::sym::ean8::EAN8;
-use barcoders::sym::tf::TF;
 use byteorder::{LittleEndian, WriteBytesExt};
 
 #[cfg(feature = "graphics")]
-use crate::domain::Graphic;
+use image::{DynamicImage, GrayImage, Luma};
+#[cfg(feature = "graphics")]
+use qrcodegen::{QrCode, QrCodeEcc};
+
+#[cfg(feature = "fonts")]
+use crate::domain::TextImage;
 use crate::domain::{
-  Alignment, Barcode, BarcodeFont, BarcodeSystem, Font, Hardware, Qr, UnderlineMode,
+  Alignment, AsbReport, Aztec, AztecMode, Barcode, BarcodeFont, Capabilities, DataMatrix,
+  DataMatrixSymbol, Font, GraphicSize, Hardware, Pdf417, Qr, StatusKind, UnderlineMode,
 };
+#[cfg(feature = "graphics")]
+use crate::domain::{BarcodeTextPosition, Graphic, GraphicDensity, GraphicMode, QrCorrectionLevel};
 use crate::error::{PrinterError, Result};
-use crate::io::{constants, Encoder};
+use crate::io::{constants, symbology, Encoder};
 
 #[derive(Default)]
 pub struct Protocol {
   encoder: Encoder,
+  capabilities: Capabilities,
+  // the line spacing set with `linespacing`, restored after column graphics
+  linespacing: Option<u8>,
+  // the font set with `font`, restored after the text of rasterised barcodes
+  font: Option<Font>,
+  #[cfg(feature = "fonts")]
+  complex_text: Option<TextImage>,
 }
 
 impl Protocol {
   pub fn new(encoder: Encoder) -> Self {
-    Self { encoder }
+    Self::with_capabilities(encoder, Capabilities::default())
+  }
+
+  pub fn with_capabilities(encoder: Encoder, capabilities: Capabilities) -> Self {
+    Self {
+      encoder,
+      capabilities,
+      linespacing: None,
+      font: None,
+      #[cfg(feature = "fonts")]
+      complex_text: None,
+    }
+  }
+
+  pub fn capabilities(&self) -> &Capabilities {
+    &self.capabilities
+  }
+
+  /// Sets how lines with right-to-left or complex script text are rendered when printed, or
+  /// `None` to send all text through the encoder
+  #[cfg(feature = "fonts")]
+  pub fn set_complex_text(&mut self, complex_text: Option<TextImage>) {
+    self.complex_text = complex_text;
   }
 
   pub fn hardware(&mut self, hardware: Hardware) -> &[u8] {
@@ -34,6 +66,7 @@ impl Protocol {
   }
 
   pub fn font(&mut self, font: Font) -> &[u8] {
+    self.font = Some(font.clone());
     match font {
       Font::A => constants::FONT_A,
       Font::B => constants::FONT_B,
@@ -61,6 +94,7 @@ impl Protocol {
     let mut buffer = Vec::new();
     buffer.write_all(constants::TEXT_LINESPACING)?;
     buffer.write_u8(height)?;
+    self.linespacing = Some(height);
     Ok(buffer)
   }
 
@@ -73,6 +107,7 @@ impl Protocol {
   }
 
   pub fn reset_linespacing(&mut self) -> &[u8] {
+    self.linespacing = None;
     constants::TEXT_RESET_LINESPACING
   }
 
@@ -112,10 +147,14 @@ impl Protocol {
   }
 
   pub fn reset(&mut self) -> &[u8] {
+    self.linespacing = None;
+    self.font = None;
     constants::HARDWARE_RESET
   }
 
   pub fn init(&mut self) -> &[u8] {
+    self.linespacing = None;
+    self.font = None;
     constants::HARDWARE_INIT
   }
 
@@ -168,10 +207,42 @@ impl Protocol {
   }
 
   pub fn print(&mut self, text: &str) -> Result<Vec<u8>> {
+    #[cfg(feature = "fonts")]
+    if let Some(complex_text) = self.complex_text.take() {
+      // moved out while printing, as the graphics need the protocol mutably
+      let buffer = self.print_complex(&complex_text, text);
+      self.complex_text = Some(complex_text);
+      return buffer;
+    }
     let encoded = self.encoder.encode(text)?;
     Ok(encoded)
   }
 
+  /// Encodes the text, except for lines that need shaping, which are rendered as graphics
+  #[cfg(feature = "fonts")]
+  fn print_complex(&mut self, complex_text: &TextImage, text: &str) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut plain = String::new();
+
+    for line in text.split_inclusive('\n') {
+      let content = line.strip_suffix('\n').unwrap_or(line);
+      if TextImage::requires_shaping(content) {
+        buffer.write_all(&self.encoder.encode(&plain)?)?;
+        plain.clear();
+        let graphic = complex_text.text_graphic(content)?;
+        buffer.write_all(&self.graphic(graphic)?)?;
+        if line.ends_with('\n') {
+          plain.push('\n');
+        }
+      } else {
+        plain.push_str(line);
+      }
+    }
+
+    buffer.write_all(&self.encoder.encode(&plain)?)?;
+    Ok(buffer)
+  }
+
   pub fn println(&mut self, text: &str) -> Result<Vec<u8>> {
     let feed = self.feed(1)?;
     let print = self.print(text)?;
@@ -190,7 +261,45 @@ impl Protocol {
     let level = qr.correction_level().into();
     let model = qr.model().into();
     let size = qr.size();
-    let text = qr.text();
+
+    if !(1..=16).contains(&size) {
+      return Err(PrinterError::input(format!(
+        "Invalid QR size {size}, expected a value between 1 and 16"
+      )));
+    }
+
+    if model != constants::QR_MODEL_1 && model != constants::QR_MODEL_2 {
+      return Err(PrinterError::input(format!(
+        "Invalid QR model {}",
+        qr.model()
+      )));
+    }
+
+    let data = match qr.bytes() {
+      Some(bytes) => bytes.to_vec(),
+      None => self.encoder.encode(qr.text())?,
+    };
+
+    if data.is_empty() {
+      return Err(PrinterError::input("QR data is empty"));
+    }
+
+    if qr.raster() {
+      return self.qr_raster(&qr, &data);
+    }
+
+    let capacity = qr.capacity(&data);
+    if data.len() > capacity {
+      return Err(PrinterError::input(format!(
+        "QR data is {} bytes, the maximum for model {} with correction level {} is {capacity}",
+        data.len(),
+        qr.model(),
+        qr.correction_level()
+      )));
+    }
+
+    let length = u16::try_from(data.len() + 3)
+      .map_err(|_| PrinterError::input("QR data exceeds the maximum length"))?;
 
     let mut buffer = Vec::new();
 
@@ -208,9 +317,9 @@ impl Protocol {
 
     // store the data
     buffer.write_all(constants::QR_DATA_STORE_PREFIX)?;
-    buffer.write_u16::<LittleEndian>((text.len() + 3) as u16)?;
+    buffer.write_u16::<LittleEndian>(length)?;
     buffer.write_all(constants::QR_DATA_STORE_SUFFIX)?;
-    buffer.write_all(text.as_bytes())?;
+    buffer.write_all(data.as_slice())?;
 
     // print the QR code
     buffer.write_all(constants::QR_PRINT)?;
@@ -218,47 +327,307 @@ impl Protocol {
     Ok(buffer)
   }
 
-  pub fn barcode(&mut self, barcode: Barcode) -> Result<Vec<u8>> {
-    let mut buffer = Vec::new();
+  #[cfg(feature = "graphics")]
+  #[allow(clippy::cast_possible_wrap)]
+  fn qr_raster(&mut self, qr: &Qr, data: &[u8]) -> Result<Vec<u8>> {
+    let level = match qr.correction_level() {
+      QrCorrectionLevel::Low => QrCodeEcc::Low,
+      QrCorrectionLevel::Medium => QrCodeEcc::Medium,
+      QrCorrectionLevel::Quartile => QrCodeEcc::Quartile,
+      QrCorrectionLevel::High => QrCodeEcc::High,
+    };
 
-    let (system, encoded) = match barcode.system() {
-      BarcodeSystem::UpcA => {
-        let encoder =
-          UPCA::new(barcode.text()).map_err(|_| PrinterError::input("Invalid UPCA barcode"))?;
-        (constants::BARCODE_TYPE_UPC_A, encoder.encode())
-      }
-      BarcodeSystem::UpcE => {
-        let encoder =
-          UPCA::new(barcode.text()).map_err(|_| PrinterError::input("Invalid UPCA barcode"))?;
-        (constants::BARCODE_TYPE_UPC_E, encoder.encode())
-      }
-      BarcodeSystem::Ean13 => {
-        let encoder =
-          EAN13::new(barcode.text()).map_err(|_| PrinterError::input("Invalid EAN13 barcode"))?;
-        (constants::BARCODE_TYPE_JAN13_EAN13, encoder.encode())
-      }
-      BarcodeSystem::Ean8 => {
-        let encoder =
-          EAN8::new(barcode.text()).map_err(|_| PrinterError::input("Invalid EAN8 barcode"))?;
-        (constants::BARCODE_TYPE_JAN8_EAN8, encoder.encode())
-      }
-      BarcodeSystem::Code39 => {
-        let encoder =
-          Code39::new(barcode.text()).map_err(|_| PrinterError::input("Invalid Code39 barcode"))?;
-        (constants::BARCODE_TYPE_CODE39, encoder.encode())
-      }
-      BarcodeSystem::Itf => {
-        let encoder = TF::interleaved(barcode.text())
-          .map_err(|_| PrinterError::input("Invalid ITF barcode"))?;
-        (constants::BARCODE_TYPE_ITF, encoder.encode())
+    // prefer the text encoding as it picks the most compact segment modes
+    let code = match std::str::from_utf8(data) {
+      Ok(text) => QrCode::encode_text(text, level),
+      Err(_) => QrCode::encode_binary(data, level),
+    }
+    .map_err(|err| PrinterError::input(format!("Could not generate QR code: {err}")))?;
+
+    // the size is lowered until the symbol fits the paper
+    let modules = code.size().unsigned_abs() + 2 * u32::from(qr.quiet_zone());
+    let max_scale = constants::QR_RASTER_WIDTH_MAX / modules;
+    if max_scale == 0 {
+      return Err(PrinterError::input(format!(
+        "QR code of {modules} modules is wider than the paper, expected at most {} modules",
+        constants::QR_RASTER_WIDTH_MAX
+      )));
+    }
+    let scale = u32::from(qr.size()).min(max_scale);
+    let quiet_zone = i32::from(qr.quiet_zone());
+    let dimension = modules * scale;
+
+    let img = GrayImage::from_fn(dimension, dimension, |x, y| {
+      let module_x = (x / scale) as i32 - quiet_zone;
+      let module_y = (y / scale) as i32 - quiet_zone;
+      // modules outside the symbol are light, which forms the quiet zone
+      if code.get_module(module_x, module_y) {
+        Luma([0])
+      } else {
+        Luma([255])
       }
-      BarcodeSystem::Codabar => {
-        let encoder = Codabar::new(barcode.text())
-          .map_err(|_| PrinterError::input("Invalid Codabar barcode"))?;
-        (constants::BARCODE_TYPE_CODABAR, encoder.encode())
+    });
+
+    let graphic = Graphic::from_image(
+      DynamicImage::ImageLuma8(img),
+      GraphicDensity::Full,
+      dimension,
+      GraphicSize::Normal,
+    );
+
+    self.graphic(graphic)
+  }
+
+  #[cfg(not(feature = "graphics"))]
+  fn qr_raster(&mut self, _qr: &Qr, _data: &[u8]) -> Result<Vec<u8>> {
+    Err(PrinterError::configuration(
+      "Rasterised QR codes require the graphics feature",
+    ))
+  }
+
+  pub fn pdf417(&mut self, pdf417: Pdf417) -> Result<Vec<u8>> {
+    let columns = pdf417.columns();
+    let rows = pdf417.rows();
+    let module_width = pdf417.module_width();
+    let row_height = pdf417.row_height();
+    let level = pdf417.correction_level();
+
+    if columns > 30 {
+      return Err(PrinterError::input(format!(
+        "Invalid PDF417 columns {columns}, expected a value between 0 and 30"
+      )));
+    }
+
+    if rows != 0 && !(3..=90).contains(&rows) {
+      return Err(PrinterError::input(format!(
+        "Invalid PDF417 rows {rows}, expected 0 or a value between 3 and 90"
+      )));
+    }
+
+    let validate = |value: u8, name: &str| -> Result<()> {
+      if (2..=8).contains(&value) {
+        Ok(())
+      } else {
+        Err(PrinterError::input(format!(
+          "Invalid PDF417 {name} {value}, expected a value between 2 and 8"
+        )))
       }
     };
 
+    validate(module_width, "module width")?;
+    validate(row_height, "row height")?;
+
+    if level > 8 {
+      return Err(PrinterError::input(format!(
+        "Invalid PDF417 correction level {level}, expected a value between 0 and 8"
+      )));
+    }
+
+    let data = self.encoder.encode(pdf417.text())?;
+    let length = u16::try_from(data.len() + 3)
+      .map_err(|_| PrinterError::input("PDF417 data exceeds the maximum length"))?;
+
+    let mut buffer = Vec::new();
+
+    // set the number of columns and rows
+    buffer.write_all(constants::PDF417_COLUMNS)?;
+    buffer.write_u8(columns)?;
+    buffer.write_all(constants::PDF417_ROWS)?;
+    buffer.write_u8(rows)?;
+
+    // set the module width and row height
+    buffer.write_all(constants::PDF417_MODULE_WIDTH)?;
+    buffer.write_u8(module_width)?;
+    buffer.write_all(constants::PDF417_ROW_HEIGHT)?;
+    buffer.write_u8(row_height)?;
+
+    // set the error correction level
+    buffer.write_all(constants::PDF417_CORRECTION_ERROR_LEVEL)?;
+    buffer.write_u8(0x30 + level)?;
+
+    // select standard or truncated PDF417
+    buffer.write_all(constants::PDF417_OPTIONS)?;
+    if pdf417.truncated() {
+      buffer.write_u8(constants::PDF417_TRUNCATED)?;
+    } else {
+      buffer.write_u8(constants::PDF417_STANDARD)?;
+    }
+
+    // store the data
+    buffer.write_all(constants::PDF417_DATA_STORE_PREFIX)?;
+    buffer.write_u16::<LittleEndian>(length)?;
+    buffer.write_all(constants::PDF417_DATA_STORE_SUFFIX)?;
+    buffer.write_all(data.as_slice())?;
+
+    // print the PDF417 symbol
+    buffer.write_all(constants::PDF417_PRINT)?;
+
+    Ok(buffer)
+  }
+
+  pub fn data_matrix(&mut self, data_matrix: DataMatrix) -> Result<Vec<u8>> {
+    let symbol = data_matrix.symbol();
+    let rows = data_matrix.rows();
+    let size = data_matrix.size();
+
+    // square symbols have the same number of rows and columns
+    let columns = match symbol {
+      DataMatrixSymbol::Square => 0,
+      DataMatrixSymbol::Rectangle => data_matrix.columns(),
+    };
+
+    let (valid_rows, valid_columns) = match symbol {
+      DataMatrixSymbol::Square => (constants::DATA_MATRIX_SQUARE_SIZES, &[][..]),
+      DataMatrixSymbol::Rectangle => (
+        constants::DATA_MATRIX_RECTANGLE_ROWS,
+        constants::DATA_MATRIX_RECTANGLE_COLUMNS,
+      ),
+    };
+
+    if rows != 0 && !valid_rows.contains(&rows) {
+      return Err(PrinterError::input(format!(
+        "Invalid DataMatrix rows {rows} for a {symbol} symbol"
+      )));
+    }
+
+    if columns != 0 && !valid_columns.contains(&columns) {
+      return Err(PrinterError::input(format!(
+        "Invalid DataMatrix columns {columns} for a {symbol} symbol"
+      )));
+    }
+
+    if !(2..=16).contains(&size) {
+      return Err(PrinterError::input(format!(
+        "Invalid DataMatrix size {size}, expected a value between 2 and 16"
+      )));
+    }
+
+    let data = self.encoder.encode(data_matrix.text())?;
+    let length = u16::try_from(data.len() + 3)
+      .map_err(|_| PrinterError::input("DataMatrix data exceeds the maximum length"))?;
+
+    let mut buffer = Vec::new();
+
+    // select the symbol type and its number of rows and columns
+    buffer.write_all(constants::DATA_MATRIX_SYMBOL)?;
+    buffer.write_u8(symbol.into())?;
+    buffer.write_u8(rows)?;
+    buffer.write_u8(columns)?;
+
+    // set the module size
+    buffer.write_all(constants::DATA_MATRIX_SIZE)?;
+    buffer.write_u8(size)?;
+
+    // store the data
+    buffer.write_all(constants::DATA_MATRIX_DATA_STORE_PREFIX)?;
+    buffer.write_u16::<LittleEndian>(length)?;
+    buffer.write_all(constants::DATA_MATRIX_DATA_STORE_SUFFIX)?;
+    buffer.write_all(data.as_slice())?;
+
+    // print the DataMatrix symbol
+    buffer.write_all(constants::DATA_MATRIX_PRINT)?;
+
+    Ok(buffer)
+  }
+
+  pub fn aztec(&mut self, aztec: Aztec) -> Result<Vec<u8>> {
+    let mode = aztec.mode();
+    let layers = aztec.layers();
+    let level = aztec.correction_level();
+    let size = aztec.size();
+
+    let max_layers = match mode {
+      AztecMode::FullRange => 32,
+      AztecMode::Compact => 4,
+    };
+
+    let min_layers = match mode {
+      AztecMode::FullRange => 4,
+      AztecMode::Compact => 1,
+    };
+
+    if layers != 0 && !(min_layers..=max_layers).contains(&layers) {
+      return Err(PrinterError::input(format!(
+        "Invalid Aztec layers {layers}, expected 0 or a value between {min_layers} and {max_layers}"
+      )));
+    }
+
+    if !(5..=95).contains(&level) {
+      return Err(PrinterError::input(format!(
+        "Invalid Aztec correction level {level}, expected a value between 5 and 95"
+      )));
+    }
+
+    if !(2..=16).contains(&size) {
+      return Err(PrinterError::input(format!(
+        "Invalid Aztec size {size}, expected a value between 2 and 16"
+      )));
+    }
+
+    let data = self.encoder.encode(aztec.text())?;
+    let length = u16::try_from(data.len() + 3)
+      .map_err(|_| PrinterError::input("Aztec data exceeds the maximum length"))?;
+
+    let mut buffer = Vec::new();
+
+    // select the mode and the number of data layers
+    buffer.write_all(constants::AZTEC_MODE)?;
+    buffer.write_u8(mode.into())?;
+    buffer.write_u8(layers)?;
+
+    // set the module size
+    buffer.write_all(constants::AZTEC_SIZE)?;
+    buffer.write_u8(size)?;
+
+    // set the error correction level
+    buffer.write_all(constants::AZTEC_CORRECTION_ERROR_LEVEL)?;
+    buffer.write_u8(level)?;
+
+    // store the data
+    buffer.write_all(constants::AZTEC_DATA_STORE_PREFIX)?;
+    buffer.write_u16::<LittleEndian>(length)?;
+    buffer.write_all(constants::AZTEC_DATA_STORE_SUFFIX)?;
+    buffer.write_all(data.as_slice())?;
+
+    // print the Aztec symbol
+    buffer.write_all(constants::AZTEC_PRINT)?;
+
+    Ok(buffer)
+  }
+
+  pub fn barcode(&mut self, barcode: Barcode) -> Result<Vec<u8>> {
+    let width = barcode.width();
+    let height = barcode.height();
+
+    // rasterised barcodes are not limited to the module widths of the printer
+    let (min, max) = if barcode.raster() {
+      (
+        constants::BARCODE_RASTER_WIDTH_MIN,
+        constants::BARCODE_RASTER_WIDTH_MAX,
+      )
+    } else {
+      (constants::BARCODE_WIDTH_MIN, constants::BARCODE_WIDTH_MAX)
+    };
+    if !(min..=max).contains(&width) {
+      return Err(PrinterError::input(format!(
+        "Invalid barcode width {width}, expected a value between {min} and {max}"
+      )));
+    }
+
+    if height == 0 {
+      return Err(PrinterError::input(format!(
+        "Invalid barcode height {height}, expected a value between 1 and 255"
+      )));
+    }
+
+    if barcode.raster() {
+      return self.barcode_raster(&barcode);
+    }
+
+    let mut buffer = Vec::new();
+
+    let (system, data) = symbology::encode(&barcode)?;
+
     let font = match barcode.font() {
       BarcodeFont::A => constants::BARCODE_FONT_A,
       BarcodeFont::B => constants::BARCODE_FONT_B,
@@ -266,16 +635,13 @@ impl Protocol {
 
     let text_position = barcode.text_position().into();
 
-    // set the barcode system
-    buffer.write_all(system)?;
-
     // set the height of the barcode
     buffer.write_all(constants::BARCODE_HEIGHT)?;
-    buffer.write_u8(0x68)?;
+    buffer.write_u8(height)?;
 
     // set the width of the barcode
     buffer.write_all(constants::BARCODE_WIDTH)?;
-    buffer.write_u8(0x02)?;
+    buffer.write_u8(width)?;
 
     // Set the barcode font
     buffer.write_all(font)?;
@@ -284,115 +650,608 @@ impl Protocol {
     buffer.write_all(constants::BARCODE_TEXT_POSITION)?;
     buffer.write_u8(text_position)?;
 
-    // set the barcode data
-    buffer.write_all(encoded.as_slice())?;
+    // set the barcode system, this must be directly followed by the data
+    buffer.write_all(system)?;
+
+    if system[2] < constants::BARCODE_FUNCTION_B {
+      // function A: the data is terminated with a null character
+      buffer.write_all(data.as_slice())?;
+      buffer.push(constants::NIL);
+    } else {
+      // function B: the data is prefixed with its length
+      let length = u8::try_from(data.len())
+        .map_err(|_| PrinterError::input("Barcode data exceeds 255 bytes"))?;
+      buffer.write_u8(length)?;
+      buffer.write_all(data.as_slice())?;
+    }
+
+    Ok(buffer)
+  }
+
+  #[cfg(feature = "graphics")]
+  fn barcode_raster(&mut self, barcode: &Barcode) -> Result<Vec<u8>> {
+    let modules = symbology::modules(barcode)?;
+    let module_width = u32::from(barcode.width());
+    let height = u32::from(barcode.height());
+
+    let quiet_zone = constants::BARCODE_RASTER_QUIET_ZONE;
+    let width = (modules.len() as u32 + 2 * quiet_zone) * module_width;
+
+    let img = GrayImage::from_fn(width, height, |x, _| {
+      let module = (x / module_width).checked_sub(quiet_zone);
+      match module.and_then(|module| modules.get(module as usize)) {
+        Some(1) => Luma([0]),
+        _ => Luma([255]),
+      }
+    });
+
+    let graphic = Graphic::from_image(
+      DynamicImage::ImageLuma8(img),
+      GraphicDensity::Full,
+      width,
+      GraphicSize::Normal,
+    );
+
+    // the human readable text is printed as text in the barcode font, after which the font
+    // set with `font` is restored
+    let mut hri = match barcode.font() {
+      BarcodeFont::A => constants::FONT_A.to_vec(),
+      BarcodeFont::B => constants::FONT_B.to_vec(),
+    };
+    hri.extend(symbology::hri(barcode)?);
+    let font = self.font.clone();
+    hri.extend_from_slice(self.font(font.clone().unwrap_or(Font::A)));
+    self.font = font;
+
+    let mut buffer = Vec::new();
+    let position = barcode.text_position();
+
+    if matches!(
+      position,
+      BarcodeTextPosition::Above | BarcodeTextPosition::Both
+    ) {
+      buffer.write_all(&hri)?;
+      buffer.write_all(constants::EOL.as_bytes())?;
+    }
 
-    // terminate the barcode data with a null character
-    buffer.push(constants::NIL);
+    buffer.write_all(&self.graphic(graphic)?)?;
+
+    if matches!(
+      position,
+      BarcodeTextPosition::Below | BarcodeTextPosition::Both
+    ) {
+      buffer.write_all(&hri)?;
+      buffer.write_all(constants::EOL.as_bytes())?;
+    }
 
     Ok(buffer)
   }
 
+  #[cfg(not(feature = "graphics"))]
+  fn barcode_raster(&mut self, _barcode: &Barcode) -> Result<Vec<u8>> {
+    Err(PrinterError::configuration(
+      "Rasterised barcodes require the graphics feature",
+    ))
+  }
+
   #[cfg(feature = "graphics")]
   pub fn graphic(&mut self, graphic: Graphic) -> Result<Vec<u8>> {
+    match self.capabilities.graphic_mode() {
+      GraphicMode::Raster => self.graphic_raster(&graphic),
+      GraphicMode::Column8 => self.graphic_column(&graphic, 8),
+      GraphicMode::Column24 => self.graphic_column(&graphic, 24),
+      GraphicMode::Graphics => self.graphic_store(&graphic),
+    }
+  }
+
+  /// The scale of the graphic on each axis, combining its size and density
+  #[cfg(feature = "graphics")]
+  fn graphic_scale(graphic: &Graphic) -> Result<u8> {
+    let size = u8::from(graphic.size());
+    let density = u8::from(graphic.density());
+
+    // both the size and the density double the dots, so they cannot be combined on one axis
+    if size & density != 0 {
+      return Err(PrinterError::input(
+        "Graphic size cannot be doubled on an axis printed at half density",
+      ));
+    }
+    Ok(size | density)
+  }
+
+  /// The number of rows sent in each command for tall graphics
+  #[cfg(feature = "graphics")]
+  fn band_height(&self, height: u32) -> u32 {
+    match self.capabilities.raster_band_height() {
+      0 => height.max(1),
+      band_height => u32::from(band_height),
+    }
+  }
+
+  #[cfg(feature = "graphics")]
+  fn graphic_raster(&mut self, graphic: &Graphic) -> Result<Vec<u8>> {
+    let scale = Self::graphic_scale(graphic)?;
+
+    let bitmap = graphic.bitmap();
+    let (width, height) = bitmap.dimensions();
+    let width_bytes =
+      u16::try_from((width + 7) / 8).map_err(|_| PrinterError::input("Graphic is too wide"))?;
+
+    // tall graphics are sent in bands so each command fits in the printer's buffer
+    let band_height = self.band_height(height);
+    let rows = Self::raster_rows(&bitmap);
+
     let mut buffer: Vec<u8> = Vec::new();
 
-    // write command header
-    buffer.write_all(constants::IMAGE_HEADER)?;
+    for band in rows.chunks((u32::from(width_bytes) * band_height) as usize) {
+      let band_height = u16::try_from(band.len() / usize::from(width_bytes))
+        .map_err(|_| PrinterError::input("Graphic is too tall"))?;
 
-    buffer.write_u8(graphic.size().into())?;
+      // write command header
+      buffer.write_all(constants::IMAGE_HEADER)?;
 
-    // set the width and height
-    buffer.write_u16::<LittleEndian>(graphic.width_bytes())?;
-    buffer.write_u16::<LittleEndian>(graphic.height())?;
+      buffer.write_u8(scale)?;
 
-    let density = graphic.density();
-    let (width, height) = graphic.dimensions();
+      // set the width and height
+      buffer.write_u16::<LittleEndian>(width_bytes)?;
+      buffer.write_u16::<LittleEndian>(band_height)?;
 
-    // Iterating over the height and width of the graphic
-    for y in 0..height {
-      for x in (0..width).step_by(density as usize) {
-        let mut byte = 0u8;
+      buffer.write_all(band)?;
+    }
 
-        // Processing 8 bits per byte
-        for bit in 0..8 {
-          let x_offset = x + bit;
+    Ok(buffer)
+  }
 
-          // Breaking the loop if x_offset exceeds the width
-          if x_offset >= width {
-            break;
-          }
+  /// Prints the graphic as `ESC *` bit images, one line of 8 or 24 dot columns at a time
+  #[cfg(feature = "graphics")]
+  fn graphic_column(&mut self, graphic: &Graphic, dots: u32) -> Result<Vec<u8>> {
+    if graphic.size() != &GraphicSize::Normal {
+      return Err(PrinterError::input(
+        "Column graphics can only be printed at normal size",
+      ));
+    }
+    if graphic.density().vertical_scale() != 1 {
+      return Err(PrinterError::input(
+        "Column graphics cannot be printed at half vertical density",
+      ));
+    }
+
+    let double_density = graphic.density().horizontal_scale() == 1;
+    let mode = match (dots, double_density) {
+      (8, false) => constants::IMAGE_COLUMN_8_SINGLE_DENSITY,
+      (8, true) => constants::IMAGE_COLUMN_8_DOUBLE_DENSITY,
+      (_, false) => constants::IMAGE_COLUMN_24_SINGLE_DENSITY,
+      (_, true) => constants::IMAGE_COLUMN_24_DOUBLE_DENSITY,
+    };
+
+    // 8-dot columns are printed at a third of the vertical resolution
+    let vertical = if dots == 8 { 3 } else { 1 };
+    let bitmap = graphic.scaled_bitmap(graphic.density().horizontal_scale(), vertical);
+    let (width, height) = bitmap.dimensions();
+    let columns = u16::try_from(width).map_err(|_| PrinterError::input("Graphic is too wide"))?;
 
-          let is_black = graphic.pixel(u32::from(x_offset), u32::from(y)).0[0] <= 128;
+    let mut buffer: Vec<u8> = Vec::new();
 
-          // Shift byte to the left, adding the pixel value at the end
-          byte = (byte << 1) | u8::from(is_black);
+    // the lines of columns have to touch, so the line spacing matches their height
+    buffer.write_all(constants::TEXT_LINESPACING)?;
+    buffer.write_u8(constants::IMAGE_COLUMN_LINESPACING)?;
+
+    for top in (0..height).step_by(dots as usize) {
+      buffer.write_all(constants::IMAGE_COLUMN)?;
+      buffer.write_u8(mode)?;
+      buffer.write_u16::<LittleEndian>(columns)?;
+
+      for x in 0..width {
+        for slice in 0..dots / 8 {
+          let mut byte = 0u8;
+          for bit in 0..8 {
+            let y = top + slice * 8 + bit;
+            if y < height && bitmap.get_pixel(x, y).0[0] == 0 {
+              byte |= 0x80 >> bit;
+            }
+          }
+          buffer.write_u8(byte)?;
         }
+      }
+
+      buffer.write_all(constants::EOL.as_bytes())?;
+    }
 
-        // Write the constructed byte to the buffer
-        buffer.write_u8(byte)?;
+    // restores the line spacing set before the graphic
+    match self.linespacing {
+      Some(height) => {
+        buffer.write_all(constants::TEXT_LINESPACING)?;
+        buffer.write_u8(height)?;
       }
+      None => buffer.write_all(constants::TEXT_RESET_LINESPACING)?,
     }
 
     Ok(buffer)
   }
-}
 
-#[cfg(test)]
-mod tests {
-  use crate::BarcodeTextPosition;
+  /// Stores the graphic in the print buffer with `GS ( L` (or `GS 8 L` for large graphics)
+  /// and then prints it
+  #[cfg(feature = "graphics")]
+  fn graphic_store(&mut self, graphic: &Graphic) -> Result<Vec<u8>> {
+    let scale = Self::graphic_scale(graphic)?;
 
-  use super::*;
+    let bitmap = graphic.bitmap();
+    let (width, height) = bitmap.dimensions();
+    let width_dots =
+      u16::try_from(width).map_err(|_| PrinterError::input("Graphic is too wide"))?;
+    let width_bytes = ((width + 7) / 8) as usize;
 
-  macro_rules! join {
-    ($base:expr, $($byte:expr),*) => {{
-        let mut result = $base.to_vec();
-        $(result.push($byte);)*
-        result
-    }};
-  }
+    let band_height = self.band_height(height);
+    let rows = Self::raster_rows(&bitmap);
 
-  #[test]
-  fn test_feed() -> Result<()> {
-    let mut protocol = Protocol::default();
-    assert_eq!(
-      protocol.feed(1)?,
-      join!(constants::PAPER_FEED_FORWARD, 0x01)
-    );
-    assert_eq!(
-      protocol.feed(5)?,
-      join!(constants::PAPER_FEED_FORWARD, 0x05)
-    );
+    let mut buffer: Vec<u8> = Vec::new();
 
-    Ok(())
+    for band in rows.chunks(width_bytes * band_height as usize) {
+      let band_height = u16::try_from(band.len() / width_bytes)
+        .map_err(|_| PrinterError::input("Graphic is too tall"))?;
+
+      let mut data = constants::GRAPHICS_RASTER_STORE.to_vec();
+      data.write_u8(1 + (scale & 0x01))?;
+      data.write_u8(1 + (scale >> 1))?;
+      data.write_u8(constants::GRAPHICS_COLOUR_1)?;
+      data.write_u16::<LittleEndian>(width_dots)?;
+      data.write_u16::<LittleEndian>(band_height)?;
+      data.write_all(band)?;
+
+      Self::graphics_command(&mut buffer, &data)?;
+      buffer.write_all(constants::GRAPHICS_PRINT)?;
+    }
+
+    Ok(buffer)
   }
 
-  #[test]
-  fn test_reverse_feed() -> Result<()> {
-    let mut protocol = Protocol::default();
-    assert_eq!(
-      protocol.reverse_feed(1)?,
-      join!(constants::PAPER_FEED_REVERSE, 0x01)
-    );
-    assert_eq!(
-      protocol.reverse_feed(5)?,
-      join!(constants::PAPER_FEED_REVERSE, 0x05)
-    );
+  /// Writes a `GS ( L` command, or `GS 8 L` when the data does not fit a 2 byte length
+  #[cfg(feature = "graphics")]
+  fn graphics_command(buffer: &mut Vec<u8>, data: &[u8]) -> Result<()> {
+    if let Ok(length) = u16::try_from(data.len()) {
+      buffer.write_all(constants::GRAPHICS_STORE)?;
+      buffer.write_u16::<LittleEndian>(length)?;
+    } else {
+      let length =
+        u32::try_from(data.len()).map_err(|_| PrinterError::input("Graphic is too large"))?;
+      buffer.write_all(constants::GRAPHICS_STORE_LARGE)?;
+      buffer.write_u32::<LittleEndian>(length)?;
+    }
+    buffer.write_all(data)?;
     Ok(())
   }
 
-  #[test]
-  fn test_font() -> Result<()> {
-    let mut protocol = Protocol::default();
-    assert_eq!(protocol.font(Font::A), constants::FONT_A);
-    assert_eq!(protocol.font(Font::B), constants::FONT_B);
-    assert_eq!(protocol.font(Font::C), constants::FONT_C);
-    Ok(())
+  /// Stores a graphic in the printer's non-volatile memory under a key of two printable
+  /// characters, replacing any graphic already stored under that key
+  #[cfg(feature = "graphics")]
+  pub fn define_nv_graphic(&mut self, key: &str, graphic: Graphic) -> Result<Vec<u8>> {
+    let key = Self::nv_graphic_key(key)?;
+
+    let bitmap = graphic.bitmap();
+    let (width, height) = bitmap.dimensions();
+    if width > constants::NV_GRAPHICS_WIDTH_MAX || height > constants::NV_GRAPHICS_HEIGHT_MAX {
+      return Err(PrinterError::input(format!(
+        "NV graphics must be at most {} by {} dots",
+        constants::NV_GRAPHICS_WIDTH_MAX,
+        constants::NV_GRAPHICS_HEIGHT_MAX
+      )));
+    }
+
+    let mut data = constants::NV_GRAPHICS_DEFINE.to_vec();
+    data.write_all(&key)?;
+    // a single colour
+    data.write_u8(0x01)?;
+    data.write_u16::<LittleEndian>(width as u16)?;
+    data.write_u16::<LittleEndian>(height as u16)?;
+    data.write_u8(constants::GRAPHICS_COLOUR_1)?;
+    data.write_all(&Self::raster_rows(&bitmap))?;
+
+    let mut buffer = Vec::new();
+    Self::graphics_command(&mut buffer, &data)?;
+    Ok(buffer)
   }
 
-  #[test]
-  fn test_align() -> Result<()> {
-    let mut protocol = Protocol::default();
+  /// Prints a graphic stored in non-volatile memory
+  pub fn nv_graphic(&mut self, key: &str, size: &GraphicSize) -> Result<Vec<u8>> {
+    let key = Self::nv_graphic_key(key)?;
+    let size = u8::from(size);
+
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::NV_GRAPHICS_PRINT)?;
+    buffer.write_all(&key)?;
+    buffer.write_u8(1 + (size & 0x01))?;
+    buffer.write_u8(1 + (size >> 1))?;
+    Ok(buffer)
+  }
+
+  pub fn delete_nv_graphic(&mut self, key: &str) -> Result<Vec<u8>> {
+    let key = Self::nv_graphic_key(key)?;
+
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::NV_GRAPHICS_DELETE)?;
+    buffer.write_all(&key)?;
+    Ok(buffer)
+  }
+
+  pub fn delete_nv_graphics(&mut self) -> &[u8] {
+    constants::NV_GRAPHICS_DELETE_ALL
+  }
+
+  /// Requests the keys of the stored NV graphics, see `parse_nv_graphic_keys`
+  pub fn nv_graphic_keys(&mut self) -> &[u8] {
+    constants::NV_GRAPHICS_KEY_LIST
+  }
+
+  /// Requests the next block of keys, after a block that has more to follow
+  pub fn nv_graphic_keys_next(&mut self) -> &[u8] {
+    constants::NV_GRAPHICS_KEY_LIST_NEXT
+  }
+
+  /// Requests the remaining NV graphics memory in bytes, see `parse_nv_graphic_capacity`
+  pub fn nv_graphic_capacity(&mut self) -> &[u8] {
+    constants::NV_GRAPHICS_REMAINING_CAPACITY
+  }
+
+  /// Requests a status byte in real time, even while the printer is offline. The reply is
+  /// decoded with `StatusKind::parse`.
+  pub fn status(&mut self, kind: &StatusKind) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::STATUS_REALTIME)?;
+    buffer.write_all(kind.parameters())?;
+    Ok(buffer)
+  }
+
+  /// Enables Automatic Status Back for the given statuses, or disables it if there are none
+  pub fn asb(&mut self, reports: &[AsbReport]) -> Result<Vec<u8>> {
+    let n = reports.iter().fold(0, |n, report| n | u8::from(report));
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::STATUS_AUTOMATIC)?;
+    buffer.write_u8(n)?;
+    Ok(buffer)
+  }
+
+  /// Parses a block of the reply to `nv_graphic_keys`. Returns the keys and whether the
+  /// printer has more keys to send.
+  pub fn parse_nv_graphic_keys(response: &[u8]) -> Result<(Vec<String>, bool)> {
+    let header = [
+      constants::NV_GRAPHICS_RESPONSE_HEADER,
+      constants::NV_GRAPHICS_RESPONSE_KEY_LIST,
+    ];
+    match Self::nv_graphic_response(response, header) {
+      Some([status, keys @ ..]) if keys.len() % 2 == 0 => {
+        let keys = keys
+          .chunks(2)
+          .map(|key| String::from_utf8_lossy(key).into_owned())
+          .collect();
+        Ok((keys, *status == constants::NV_GRAPHICS_RESPONSE_MORE))
+      }
+      _ => Err(PrinterError::Io(
+        "Unexpected reply to the NV graphics key list request".to_string(),
+      )),
+    }
+  }
+
+  /// Parses the reply to `nv_graphic_capacity`
+  pub fn parse_nv_graphic_capacity(response: &[u8]) -> Result<u32> {
+    let header = [
+      constants::NV_GRAPHICS_RESPONSE_HEADER,
+      constants::NV_GRAPHICS_RESPONSE_REMAINING_CAPACITY,
+    ];
+    Self::nv_graphic_response(response, header)
+      .and_then(|digits| std::str::from_utf8(digits).ok())
+      .and_then(|digits| digits.parse().ok())
+      .ok_or_else(|| {
+        PrinterError::Io("Unexpected reply to the NV graphics capacity request".to_string())
+      })
+  }
+
+  /// The data of a reply, between its header and the terminating NUL
+  fn nv_graphic_response(response: &[u8], header: [u8; 2]) -> Option<&[u8]> {
+    response
+      .strip_prefix(&header)
+      .and_then(|data| data.strip_suffix(&[constants::NIL]))
+  }
+
+  fn nv_graphic_key(key: &str) -> Result<[u8; 2]> {
+    match key.as_bytes() {
+      &[first, second]
+        if [first, second].iter().all(|byte| {
+          (constants::NV_GRAPHICS_KEY_MIN..=constants::NV_GRAPHICS_KEY_MAX).contains(byte)
+        }) =>
+      {
+        Ok([first, second])
+      }
+      _ => Err(PrinterError::input(
+        "NV graphic keys must be two printable ASCII characters",
+      )),
+    }
+  }
+
+  /// Replaces the NV bit images stored with `FS q`, for printers without NV graphics support.
+  /// The images are numbered from 1 in the order given.
+  #[cfg(feature = "graphics")]
+  pub fn define_nv_bit_images(&mut self, graphics: Vec<Graphic>) -> Result<Vec<u8>> {
+    let count = u8::try_from(graphics.len())
+      .ok()
+      .filter(|count| *count > 0)
+      .ok_or_else(|| PrinterError::input("Between 1 and 255 NV bit images can be defined"))?;
+
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::NV_BIT_IMAGE_DEFINE)?;
+    buffer.write_u8(count)?;
+
+    for graphic in graphics {
+      let bitmap = graphic.bitmap();
+      let (width, height) = bitmap.dimensions();
+      let (width, height) = ((width + 7) / 8, (height + 7) / 8);
+      if width > constants::NV_BIT_IMAGE_WIDTH_MAX || height > constants::NV_BIT_IMAGE_HEIGHT_MAX {
+        return Err(PrinterError::input(format!(
+          "NV bit images must be at most {} by {} dots",
+          constants::NV_BIT_IMAGE_WIDTH_MAX * 8,
+          constants::NV_BIT_IMAGE_HEIGHT_MAX * 8
+        )));
+      }
+      buffer.write_u16::<LittleEndian>(width as u16)?;
+      buffer.write_u16::<LittleEndian>(height as u16)?;
+      buffer.write_all(&Self::column_bytes(&bitmap, width * 8, height))?;
+    }
+
+    Ok(buffer)
+  }
+
+  /// Prints an NV bit image defined with `define_nv_bit_images`
+  pub fn nv_bit_image(&mut self, number: u8, size: &GraphicSize) -> Result<Vec<u8>> {
+    if number == 0 {
+      return Err(PrinterError::input("NV bit images are numbered from 1"));
+    }
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::NV_BIT_IMAGE_PRINT)?;
+    buffer.write_u8(number)?;
+    buffer.write_u8(size.into())?;
+    Ok(buffer)
+  }
+
+  /// Stores a graphic in the printer's RAM with `GS *`, replacing the previous one. The
+  /// graphic is lost when the printer is reset or turned off.
+  #[cfg(feature = "graphics")]
+  pub fn define_download_bit_image(&mut self, graphic: Graphic) -> Result<Vec<u8>> {
+    let bitmap = graphic.bitmap();
+    let (width, height) = bitmap.dimensions();
+    let (width, height) = ((width + 7) / 8, (height + 7) / 8);
+    if width > constants::DOWNLOAD_BIT_IMAGE_WIDTH_MAX
+      || height > constants::DOWNLOAD_BIT_IMAGE_HEIGHT_MAX
+      || width * height > constants::DOWNLOAD_BIT_IMAGE_BLOCKS_MAX
+    {
+      return Err(PrinterError::input(format!(
+        "Download bit images must be at most {} by {} dots and {} bytes, got {} bytes",
+        constants::DOWNLOAD_BIT_IMAGE_WIDTH_MAX * 8,
+        constants::DOWNLOAD_BIT_IMAGE_HEIGHT_MAX * 8,
+        constants::DOWNLOAD_BIT_IMAGE_BLOCKS_MAX * 8,
+        width * height * 8
+      )));
+    }
+
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::DOWNLOAD_BIT_IMAGE_DEFINE)?;
+    buffer.write_u8(width as u8)?;
+    buffer.write_u8(height as u8)?;
+    buffer.write_all(&Self::column_bytes(&bitmap, width * 8, height))?;
+    Ok(buffer)
+  }
+
+  /// Prints the graphic defined with `define_download_bit_image`
+  pub fn download_bit_image(&mut self, size: &GraphicSize) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    buffer.write_all(constants::DOWNLOAD_BIT_IMAGE_PRINT)?;
+    buffer.write_u8(size.into())?;
+    Ok(buffer)
+  }
+
+  /// Packs a bitmap into columns of `height` bytes, with the most significant bit at the top,
+  /// padding it with white dots to `width` columns
+  #[cfg(feature = "graphics")]
+  fn column_bytes(bitmap: &GrayImage, width: u32, height: u32) -> Vec<u8> {
+    let mut columns = Vec::with_capacity((width * height) as usize);
+
+    for x in 0..width {
+      for row in 0..height {
+        let mut byte = 0u8;
+        for bit in 0..8 {
+          let y = row * 8 + bit;
+          if x < bitmap.width() && y < bitmap.height() && bitmap.get_pixel(x, y).0[0] == 0 {
+            byte |= 0x80 >> bit;
+          }
+        }
+        columns.push(byte);
+      }
+    }
+
+    columns
+  }
+
+  /// Packs a bitmap into rows of bytes, 8 dots per byte with the most significant bit first.
+  /// The last byte of each row is padded with white dots.
+  #[cfg(feature = "graphics")]
+  fn raster_rows(bitmap: &GrayImage) -> Vec<u8> {
+    let (width, height) = bitmap.dimensions();
+    let width_bytes = (width + 7) / 8;
+    let mut rows = Vec::with_capacity((width_bytes * height) as usize);
+
+    for y in 0..height {
+      for column in 0..width_bytes {
+        let mut byte = 0u8;
+        for bit in 0..8 {
+          let x = column * 8 + bit;
+          if x < width && bitmap.get_pixel(x, y).0[0] == 0 {
+            byte |= 0x80 >> bit;
+          }
+        }
+        rows.push(byte);
+      }
+    }
+
+    rows
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    AztecBuilder, BarcodeSystem, BarcodeTextPosition, DataMatrixBuilder, Pdf417Builder,
+    QrCorrectionLevel, QrModel,
+  };
+
+  use super::*;
+
+  macro_rules! join {
+    ($base:expr, $($byte:expr),*) => {{
+        let mut result = $base.to_vec();
+        $(result.push($byte);)*
+        result
+    }};
+  }
+
+  #[test]
+  fn test_feed() -> Result<()> {
+    let mut protocol = Protocol::default();
+    assert_eq!(
+      protocol.feed(1)?,
+      join!(constants::PAPER_FEED_FORWARD, 0x01)
+    );
+    assert_eq!(
+      protocol.feed(5)?,
+      join!(constants::PAPER_FEED_FORWARD, 0x05)
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_reverse_feed() -> Result<()> {
+    let mut protocol = Protocol::default();
+    assert_eq!(
+      protocol.reverse_feed(1)?,
+      join!(constants::PAPER_FEED_REVERSE, 0x01)
+    );
+    assert_eq!(
+      protocol.reverse_feed(5)?,
+      join!(constants::PAPER_FEED_REVERSE, 0x05)
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_font() -> Result<()> {
+    let mut protocol = Protocol::default();
+    assert_eq!(protocol.font(Font::A), constants::FONT_A);
+    assert_eq!(protocol.font(Font::B), constants::FONT_B);
+    assert_eq!(protocol.font(Font::C), constants::FONT_C);
+    Ok(())
+  }
+
+  #[test]
+  fn test_align() -> Result<()> {
+    let mut protocol = Protocol::default();
     assert_eq!(
       protocol.align(Alignment::Left),
       constants::TEXT_JUSTIFY_LEFT
@@ -596,6 +1455,36 @@ mod tests {
   }
 
   #[test]
+  #[cfg(feature = "fonts")]
+  fn test_print_complex() -> Result<()> {
+    use crate::TextImage;
+
+    let mut builder = TextImage::builder();
+    builder
+      .font_path("resources/fonts/DejaVuSans.ttf")
+      .width(64);
+    let complex_text = builder.build()?;
+
+    let mut protocol = Protocol::default();
+    protocol.set_complex_text(Some(complex_text.clone()));
+
+    // latin text is still encoded
+    assert_eq!(protocol.print("Hello\nWorld")?, b"Hello\nWorld".to_vec());
+
+    let graphic = protocol.graphic(complex_text.text_graphic("שלום")?)?;
+    let buffer = protocol.print("Hello\nשלום\nWorld")?;
+    assert_eq!(
+      buffer,
+      [b"Hello\n".to_vec(), graphic, b"\nWorld".to_vec()].concat()
+    );
+
+    protocol.set_complex_text(None);
+    assert_eq!(protocol.print("שלום")?, "שלום".as_bytes().to_vec());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
   fn test_graphic() -> Result<()> {
     let mut builder = Graphic::builder();
     builder.path("resources/rust-logo-small.png");
@@ -616,6 +1505,331 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_graphic_bands() -> Result<()> {
+    let mut builder = Capabilities::builder();
+    builder.raster_band_height(24);
+    let mut protocol = Protocol::with_capabilities(Encoder::default(), builder.build());
+
+    let mut builder = Graphic::builder();
+    builder.gray(16, 50, vec![0; 800]);
+    let buffer = protocol.graphic(builder.build()?)?;
+
+    // bands of 24, 24 and 2 rows, each with its own header
+    let band = |height: u16| {
+      let mut band = constants::IMAGE_HEADER.to_vec();
+      band.extend_from_slice(&[0x00, 0x02, 0x00]);
+      band.extend_from_slice(&height.to_le_bytes());
+      band.extend(vec![0xFF; usize::from(height) * 2]);
+      band
+    };
+    assert_eq!(buffer, [band(24), band(24), band(2)].concat());
+
+    let mut builder = Capabilities::builder();
+    builder.raster_band_height(0);
+    let mut protocol = Protocol::with_capabilities(Encoder::default(), builder.build());
+
+    let mut builder = Graphic::builder();
+    builder.gray(16, 50, vec![0; 800]);
+    assert_eq!(protocol.graphic(builder.build()?)?, band(50));
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_graphic_column() -> Result<()> {
+    let mode = |mode: GraphicMode| {
+      let mut builder = Capabilities::builder();
+      builder.graphic_mode(mode);
+      Protocol::with_capabilities(Encoder::default(), builder.build())
+    };
+
+    // a 2 by 30 graphic with a black top row and a black left column
+    let data: Vec<u8> = (0..60)
+      .map(|i| if i < 2 || i % 2 == 0 { 0 } else { 255 })
+      .collect();
+
+    let mut builder = Graphic::builder();
+    builder.gray(2, 30, data);
+    let buffer = mode(GraphicMode::Column24).graphic(builder.build()?)?;
+
+    let mut expected = join!(constants::TEXT_LINESPACING, 24);
+    expected.extend(join!(constants::IMAGE_COLUMN, 0x21, 0x02, 0x00));
+    expected.extend([0xFF, 0xFF, 0xFF, 0x80, 0x00, 0x00]);
+    expected.push(b'\n');
+    expected.extend(join!(constants::IMAGE_COLUMN, 0x21, 0x02, 0x00));
+    expected.extend([0xFC, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    expected.push(b'\n');
+    expected.extend(constants::TEXT_RESET_LINESPACING);
+    assert_eq!(buffer, expected);
+
+    // 8-dot columns keep every third row, at half horizontal density every second column,
+    // so the top half of the graphic becomes 5 dots
+    let data = (0..60).map(|i| if i < 30 { 0 } else { 255 }).collect();
+    let mut builder = Graphic::builder();
+    builder
+      .gray(2, 30, data)
+      .density(GraphicDensity::HalfHorizontal);
+    let buffer = mode(GraphicMode::Column8).graphic(builder.build()?)?;
+
+    let mut expected = join!(constants::TEXT_LINESPACING, 24);
+    expected.extend(join!(constants::IMAGE_COLUMN, 0x00, 0x01, 0x00, 0xF8));
+    expected.push(b'\n');
+    expected.extend(join!(constants::IMAGE_COLUMN, 0x00, 0x01, 0x00, 0x00));
+    expected.push(b'\n');
+    expected.extend(constants::TEXT_RESET_LINESPACING);
+    assert_eq!(buffer, expected);
+
+    // a line spacing set before the graphic is restored after it
+    let mut protocol = mode(GraphicMode::Column8);
+    protocol.linespacing(40)?;
+    let blank = || {
+      let mut builder = Graphic::builder();
+      builder.gray(2, 30, vec![255; 60]);
+      builder.build()
+    };
+    let buffer = protocol.graphic(blank()?)?;
+    assert!(buffer.ends_with(&join!(constants::TEXT_LINESPACING, 40)));
+    protocol.reset_linespacing();
+    let buffer = protocol.graphic(blank()?)?;
+    assert!(buffer.ends_with(constants::TEXT_RESET_LINESPACING));
+
+    let mut builder = Graphic::builder();
+    builder
+      .gray(2, 30, vec![0; 60])
+      .size(GraphicSize::DoubleWidth);
+    assert!(mode(GraphicMode::Column24)
+      .graphic(builder.build()?)
+      .is_err());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_graphic_store() -> Result<()> {
+    let mut builder = Capabilities::builder();
+    builder.graphic_mode(GraphicMode::Graphics);
+    let mut protocol = Protocol::with_capabilities(Encoder::default(), builder.build());
+
+    let mut builder = Graphic::builder();
+    builder
+      .mono(10, 2, vec![0xFF, 0xC0, 0x80, 0x00])
+      .size(GraphicSize::DoubleHeight);
+    let buffer = protocol.graphic(builder.build()?)?;
+
+    let mut expected = join!(constants::GRAPHICS_STORE, 14, 0x00);
+    expected.extend(constants::GRAPHICS_RASTER_STORE);
+    expected.extend([0x01, 0x02, 0x31, 0x0A, 0x00, 0x02, 0x00]);
+    expected.extend([0xFF, 0xC0, 0x80, 0x00]);
+    expected.extend(constants::GRAPHICS_PRINT);
+    assert_eq!(buffer, expected);
+
+    // graphics over 64KB are stored with GS 8 L
+    let mut builder = Capabilities::builder();
+    builder
+      .graphic_mode(GraphicMode::Graphics)
+      .raster_band_height(0);
+    let mut protocol = Protocol::with_capabilities(Encoder::default(), builder.build());
+
+    let mut builder = Graphic::builder();
+    builder.mono(512, 1100, vec![0; 64 * 1100]);
+    let buffer = protocol.graphic(builder.build()?)?;
+
+    assert_eq!(&buffer[0..3], constants::GRAPHICS_STORE_LARGE);
+    assert_eq!(&buffer[3..7], &(64 * 1100 + 10u32).to_le_bytes());
+    assert_eq!(
+      buffer.len(),
+      7 + 10 + 64 * 1100 + constants::GRAPHICS_PRINT.len()
+    );
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_define_nv_graphic() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = Graphic::builder();
+    builder.mono(10, 2, vec![0xFF, 0xC0, 0x80, 0x00]);
+    let buffer = protocol.define_nv_graphic("L1", builder.build()?)?;
+
+    let mut expected = join!(constants::GRAPHICS_STORE, 15, 0x00);
+    expected.extend(constants::NV_GRAPHICS_DEFINE);
+    expected.extend([b'L', b'1', 0x01, 0x0A, 0x00, 0x02, 0x00, 0x31]);
+    expected.extend([0xFF, 0xC0, 0x80, 0x00]);
+    assert_eq!(buffer, expected);
+
+    let mut builder = Graphic::builder();
+    builder.mono(8, 2305, vec![0x00; 2305]);
+    assert!(protocol.define_nv_graphic("L1", builder.build()?).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_nv_graphic() -> Result<()> {
+    let mut protocol = Protocol::default();
+    assert_eq!(
+      protocol.nv_graphic("L1", &GraphicSize::Normal)?,
+      join!(constants::NV_GRAPHICS_PRINT, b'L', b'1', 0x01, 0x01)
+    );
+    assert_eq!(
+      protocol.nv_graphic("~ ", &GraphicSize::DoubleHeight)?,
+      join!(constants::NV_GRAPHICS_PRINT, b'~', b' ', 0x01, 0x02)
+    );
+    assert_eq!(
+      protocol.delete_nv_graphic("L1")?,
+      join!(constants::NV_GRAPHICS_DELETE, b'L', b'1')
+    );
+
+    assert!(protocol.nv_graphic("L", &GraphicSize::Normal).is_err());
+    assert!(protocol.nv_graphic("L12", &GraphicSize::Normal).is_err());
+    assert!(protocol.nv_graphic("L\n", &GraphicSize::Normal).is_err());
+    assert!(protocol.delete_nv_graphic("é").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_nv_graphic_responses() -> Result<()> {
+    let (keys, more) =
+      Protocol::parse_nv_graphic_keys(&[0x37, 0x72, 0x40, b'L', b'1', b'L', b'2', 0x00])?;
+    assert_eq!(keys, vec!["L1", "L2"]);
+    assert!(!more);
+
+    let (keys, more) = Protocol::parse_nv_graphic_keys(&[0x37, 0x72, 0x41, b'A', b'B', 0x00])?;
+    assert_eq!(keys, vec!["AB"]);
+    assert!(more);
+
+    assert!(Protocol::parse_nv_graphic_keys(&[0x37, 0x72, 0x40, b'L', 0x00]).is_err());
+    assert!(Protocol::parse_nv_graphic_keys(&[0x37, 0x31, 0x40, 0x00]).is_err());
+
+    assert_eq!(
+      Protocol::parse_nv_graphic_capacity(b"\x37\x311048576\x00")?,
+      1_048_576
+    );
+    assert!(Protocol::parse_nv_graphic_capacity(b"\x37\x31abc\x00").is_err());
+    assert!(Protocol::parse_nv_graphic_capacity(b"\x37\x72\x00").is_err());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_define_nv_bit_images() -> Result<()> {
+    let mut protocol = Protocol::default();
+
+    // a 10 by 9 graphic with a black top row, padded to 16 columns of 2 bytes
+    let graphic = |width: u32| {
+      let data = (0..width * 9)
+        .map(|i| if i < width { 0 } else { 255 })
+        .collect();
+      let mut builder = Graphic::builder();
+      builder.gray(width, 9, data);
+      builder.build()
+    };
+    let buffer = protocol.define_nv_bit_images(vec![graphic(10)?, graphic(8)?])?;
+
+    let mut expected = join!(constants::NV_BIT_IMAGE_DEFINE, 0x02, 0x02, 0x00, 0x02, 0x00);
+    for column in 0..16 {
+      expected.extend(if column < 10 {
+        [0x80, 0x00]
+      } else {
+        [0x00, 0x00]
+      });
+    }
+    expected.extend([0x01, 0x00, 0x02, 0x00]);
+    for _ in 0..8 {
+      expected.extend([0x80, 0x00]);
+    }
+    assert_eq!(buffer, expected);
+
+    assert!(protocol.define_nv_bit_images(vec![]).is_err());
+    assert_eq!(
+      protocol.nv_bit_image(2, &GraphicSize::DoubleWidthAndHeight)?,
+      join!(constants::NV_BIT_IMAGE_PRINT, 0x02, 0x03)
+    );
+    assert!(protocol.nv_bit_image(0, &GraphicSize::Normal).is_err());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_define_download_bit_image() -> Result<()> {
+    let mut protocol = Protocol::default();
+
+    let mut builder = Graphic::builder();
+    builder.mono(8, 8, vec![0xFF, 0, 0, 0, 0, 0, 0, 0x01]);
+    let buffer = protocol.define_download_bit_image(builder.build()?)?;
+
+    let mut expected = join!(constants::DOWNLOAD_BIT_IMAGE_DEFINE, 0x01, 0x01);
+    expected.extend([0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81]);
+    assert_eq!(buffer, expected);
+
+    // 64 by 25 blocks of 8 bytes are over the 12288 byte limit
+    let mut builder = Graphic::builder();
+    builder.mono(512, 200, vec![0; 64 * 200]);
+    assert!(protocol
+      .define_download_bit_image(builder.build()?)
+      .is_err());
+
+    // 49 blocks are over the height limit of 384 dots
+    let mut builder = Graphic::builder();
+    builder.mono(8, 392, vec![0; 392]);
+    assert!(protocol
+      .define_download_bit_image(builder.build()?)
+      .is_err());
+
+    assert_eq!(
+      protocol.download_bit_image(&GraphicSize::DoubleHeight)?,
+      join!(constants::DOWNLOAD_BIT_IMAGE_PRINT, 0x02)
+    );
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_graphic_padding() -> Result<()> {
+    let mut builder = Graphic::builder();
+    builder.mono(
+      10,
+      2,
+      vec![0b1000_0000, 0b0100_0000, 0b0111_1111, 0b1100_0000],
+    );
+
+    let mut protocol = Protocol::default();
+    let buffer = protocol.graphic(builder.build()?)?;
+
+    assert_eq!(&buffer[3..8], &[0x00, 0x02, 0x00, 0x02, 0x00]);
+    assert_eq!(
+      &buffer[8..],
+      &[0b1000_0000, 0b0100_0000, 0b0111_1111, 0b1100_0000]
+    );
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_graphic_density() -> Result<()> {
+    let mut protocol = Protocol::default();
+
+    let mut builder = Graphic::builder();
+    builder
+      .mono(16, 2, vec![0xFF; 4])
+      .density(GraphicDensity::HalfHorizontal)
+      .size(GraphicSize::DoubleHeight);
+    let buffer = protocol.graphic(builder.build()?)?;
+
+    // half the dots across, each printed double width, and every dot printed double height
+    assert_eq!(&buffer[3..8], &[0x03, 0x01, 0x00, 0x02, 0x00]);
+    assert_eq!(&buffer[8..], &[0xFF, 0xFF]);
+
+    let mut builder = Graphic::builder();
+    builder
+      .mono(16, 2, vec![0xFF; 4])
+      .density(GraphicDensity::HalfVertical)
+      .size(GraphicSize::DoubleHeight);
+    assert!(protocol.graphic(builder.build()?).is_err());
+    Ok(())
+  }
+
   #[test]
   fn test_qr() -> Result<()> {
     let mut protocol = Protocol::default();
@@ -642,6 +1856,241 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_pdf417() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = Pdf417::builder();
+    builder
+      .columns(4)
+      .module_width(2)
+      .row_height(4)
+      .correction_level(3)
+      .truncated(true)
+      .text("Hello world");
+
+    let buffer = protocol.pdf417(builder.build())?;
+
+    assert_eq!(&buffer[0..7], constants::PDF417_COLUMNS);
+    assert_eq!(buffer[7], 0x04);
+    assert_eq!(&buffer[8..15], constants::PDF417_ROWS);
+    assert_eq!(buffer[15], 0x00);
+    assert_eq!(&buffer[16..23], constants::PDF417_MODULE_WIDTH);
+    assert_eq!(buffer[23], 0x02);
+    assert_eq!(&buffer[24..31], constants::PDF417_ROW_HEIGHT);
+    assert_eq!(buffer[31], 0x04);
+    assert_eq!(&buffer[32..40], constants::PDF417_CORRECTION_ERROR_LEVEL);
+    assert_eq!(buffer[40], 0x33);
+    assert_eq!(&buffer[41..48], constants::PDF417_OPTIONS);
+    assert_eq!(buffer[48], constants::PDF417_TRUNCATED);
+    assert_eq!(&buffer[49..52], constants::PDF417_DATA_STORE_PREFIX);
+    assert_eq!(buffer[52], 0x0E);
+    assert_eq!(buffer[53], 0x00);
+    assert_eq!(&buffer[54..57], constants::PDF417_DATA_STORE_SUFFIX);
+    assert_eq!(&buffer[57..68], "Hello world".as_bytes());
+    assert_eq!(&buffer[68..76], constants::PDF417_PRINT);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_pdf417_invalid() {
+    let mut protocol = Protocol::default();
+    let invalid: [fn(&mut Pdf417Builder) -> &mut Pdf417Builder; 5] = [
+      |builder| builder.columns(31),
+      |builder| builder.rows(2),
+      |builder| builder.module_width(1),
+      |builder| builder.row_height(9),
+      |builder| builder.correction_level(9),
+    ];
+
+    for function in invalid {
+      let mut builder = Pdf417::builder();
+      function(builder.text("Hello world"));
+      assert!(protocol.pdf417(builder.build()).is_err());
+    }
+  }
+
+  #[test]
+  fn test_data_matrix() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = DataMatrix::builder();
+    builder
+      .symbol(DataMatrixSymbol::Rectangle)
+      .rows(12)
+      .columns(26)
+      .size(4)
+      .text("Hello world");
+
+    let buffer = protocol.data_matrix(builder.build())?;
+
+    assert_eq!(&buffer[0..7], constants::DATA_MATRIX_SYMBOL);
+    assert_eq!(&buffer[7..10], &[0x01, 12, 26]);
+    assert_eq!(&buffer[10..17], constants::DATA_MATRIX_SIZE);
+    assert_eq!(buffer[17], 0x04);
+    assert_eq!(&buffer[18..21], constants::DATA_MATRIX_DATA_STORE_PREFIX);
+    assert_eq!(buffer[21], 0x0E);
+    assert_eq!(buffer[22], 0x00);
+    assert_eq!(&buffer[23..26], constants::DATA_MATRIX_DATA_STORE_SUFFIX);
+    assert_eq!(&buffer[26..37], "Hello world".as_bytes());
+    assert_eq!(&buffer[37..45], constants::DATA_MATRIX_PRINT);
+
+    let mut builder = DataMatrix::builder();
+    builder.rows(24).columns(24).text("Hello world");
+    let buffer = protocol.data_matrix(builder.build())?;
+    assert_eq!(&buffer[7..10], &[0x00, 24, 0]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_data_matrix_invalid() {
+    let mut protocol = Protocol::default();
+    let invalid: [fn(&mut DataMatrixBuilder) -> &mut DataMatrixBuilder; 4] = [
+      |builder| builder.rows(11),
+      |builder| builder.symbol(DataMatrixSymbol::Rectangle).rows(10),
+      |builder| builder.symbol(DataMatrixSymbol::Rectangle).columns(20),
+      |builder| builder.size(1),
+    ];
+
+    for function in invalid {
+      let mut builder = DataMatrix::builder();
+      function(builder.text("Hello world"));
+      assert!(protocol.data_matrix(builder.build()).is_err());
+    }
+  }
+
+  #[test]
+  fn test_aztec() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = Aztec::builder();
+    builder
+      .mode(AztecMode::Compact)
+      .layers(3)
+      .correction_level(40)
+      .size(5)
+      .text("Hello world");
+
+    let buffer = protocol.aztec(builder.build())?;
+
+    assert_eq!(&buffer[0..7], constants::AZTEC_MODE);
+    assert_eq!(&buffer[7..9], &[0x01, 0x03]);
+    assert_eq!(&buffer[9..16], constants::AZTEC_SIZE);
+    assert_eq!(buffer[16], 0x05);
+    assert_eq!(&buffer[17..24], constants::AZTEC_CORRECTION_ERROR_LEVEL);
+    assert_eq!(buffer[24], 40);
+    assert_eq!(&buffer[25..28], constants::AZTEC_DATA_STORE_PREFIX);
+    assert_eq!(buffer[28], 0x0E);
+    assert_eq!(buffer[29], 0x00);
+    assert_eq!(&buffer[30..33], constants::AZTEC_DATA_STORE_SUFFIX);
+    assert_eq!(&buffer[33..44], "Hello world".as_bytes());
+    assert_eq!(&buffer[44..52], constants::AZTEC_PRINT);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_aztec_invalid() {
+    let mut protocol = Protocol::default();
+    let invalid: [fn(&mut AztecBuilder) -> &mut AztecBuilder; 5] = [
+      |builder| builder.layers(3),
+      |builder| builder.mode(AztecMode::Compact).layers(5),
+      |builder| builder.correction_level(4),
+      |builder| builder.correction_level(96),
+      |builder| builder.size(17),
+    ];
+
+    for function in invalid {
+      let mut builder = Aztec::builder();
+      function(builder.text("Hello world"));
+      assert!(protocol.aztec(builder.build()).is_err());
+    }
+  }
+
+  #[test]
+  fn test_qr_bytes() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = Qr::builder();
+    builder.bytes([0x00, 0xFF, 0x10]);
+
+    let buffer = protocol.qr(builder.build())?;
+
+    assert_eq!(buffer[28], 0x06);
+    assert_eq!(buffer[29], 0x00);
+    assert_eq!(&buffer[33..36], &[0x00, 0xFF, 0x10]);
+    assert_eq!(&buffer[36..44], constants::QR_PRINT);
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_qr_raster() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = Qr::builder();
+    builder
+      .text("Hello world")
+      .size(4)
+      .quiet_zone(2)
+      .raster(true);
+
+    let buffer = protocol.qr(builder.build())?;
+
+    // a version 1 symbol has 21 modules, plus 2 quiet zone modules on each side
+    let dimension: u16 = (21 + 4) * 4;
+    assert_eq!(&buffer[0..3], constants::IMAGE_HEADER);
+    assert_eq!(buffer[3], 0x00);
+    assert_eq!(&buffer[6..8], &dimension.to_le_bytes());
+
+    // a version 26 symbol has 121 modules, so the size is lowered to 3 dots per module
+    let mut builder = Qr::builder();
+    builder
+      .text("a".repeat(1000))
+      .model(QrModel::Model2)
+      .size(16)
+      .raster(true);
+    let buffer = protocol.qr(builder.build())?;
+    let dimension: u16 = (121 + 8) * 3;
+    assert_eq!(&buffer[4..6], &((dimension + 7) / 8).to_le_bytes());
+
+    // a quiet zone too wide for the paper
+    let mut builder = Qr::builder();
+    builder.text("Hello world").quiet_zone(255).raster(true);
+    assert!(protocol.qr(builder.build()).is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_qr_invalid() {
+    let mut protocol = Protocol::default();
+
+    for size in [0, 17] {
+      let mut builder = Qr::builder();
+      builder.size(size).text("Hello world");
+      assert!(protocol.qr(builder.build()).is_err());
+    }
+
+    let mut builder = Qr::builder();
+    builder.text("");
+    assert!(protocol.qr(builder.build()).is_err());
+
+    // model 1 holds at most 382 bytes with the medium correction level
+    let mut builder = Qr::builder();
+    builder.text("a".repeat(383));
+    assert!(protocol.qr(builder.build()).is_err());
+
+    let mut builder = Qr::builder();
+    builder.model(QrModel::Model2).text("a".repeat(383));
+    assert!(protocol.qr(builder.build()).is_ok());
+
+    let mut builder = Qr::builder();
+    builder
+      .model(QrModel::Model2)
+      .correction_level(QrCorrectionLevel::High)
+      .text("1".repeat(3058));
+    assert!(protocol.qr(builder.build()).is_err());
+  }
+
   #[test]
   fn test_barcode() -> Result<()> {
     let mut protocol = Protocol::default();
@@ -654,16 +2103,242 @@ mod tests {
 
     let buffer = protocol.barcode(barcode)?;
 
-    assert_eq!(&buffer[0..3], constants::BARCODE_TYPE_JAN13_EAN13);
-    assert_eq!(&buffer[3..5], constants::BARCODE_HEIGHT);
-    assert_eq!(buffer[5], 0x68);
-    assert_eq!(buffer[5], 0x68);
-    assert_eq!(&buffer[6..8], constants::BARCODE_WIDTH);
-    assert_eq!(buffer[8], 0x02);
-    assert_eq!(&buffer[9..12], constants::BARCODE_FONT_A);
-    assert_eq!(&buffer[12..14], constants::BARCODE_TEXT_POSITION);
-    assert_eq!(buffer[14], 0x00);
-    assert_eq!(buffer[110], 0x00);
+    assert_eq!(&buffer[0..2], constants::BARCODE_HEIGHT);
+    assert_eq!(buffer[2], 0x68);
+    assert_eq!(&buffer[3..5], constants::BARCODE_WIDTH);
+    assert_eq!(buffer[5], 0x02);
+    assert_eq!(&buffer[6..9], constants::BARCODE_FONT_A);
+    assert_eq!(&buffer[9..11], constants::BARCODE_TEXT_POSITION);
+    assert_eq!(buffer[11], 0x00);
+    assert_eq!(&buffer[12..15], constants::BARCODE_TYPE_JAN13_EAN13);
+    assert_eq!(&buffer[15..28], "1234561234560".as_bytes());
+    assert_eq!(buffer[28], constants::NIL);
+    assert_eq!(buffer.len(), 29);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_barcode_systems() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let cases = [
+      (
+        BarcodeSystem::UpcA,
+        "03600029145",
+        constants::BARCODE_TYPE_UPC_A,
+        "036000291452",
+      ),
+      (
+        BarcodeSystem::UpcA,
+        "036000291452",
+        constants::BARCODE_TYPE_UPC_A,
+        "036000291452",
+      ),
+      (
+        BarcodeSystem::Ean13,
+        "400638133393",
+        constants::BARCODE_TYPE_JAN13_EAN13,
+        "4006381333931",
+      ),
+      (
+        BarcodeSystem::Ean8,
+        "9638507",
+        constants::BARCODE_TYPE_JAN8_EAN8,
+        "96385074",
+      ),
+      (
+        BarcodeSystem::Ean8,
+        "96385074",
+        constants::BARCODE_TYPE_JAN8_EAN8,
+        "96385074",
+      ),
+      (
+        BarcodeSystem::Code39,
+        "CODE-39",
+        constants::BARCODE_TYPE_CODE39,
+        "CODE-39",
+      ),
+      (
+        BarcodeSystem::Code39,
+        "*CODE39*",
+        constants::BARCODE_TYPE_CODE39,
+        "*CODE39*",
+      ),
+      (
+        BarcodeSystem::Itf,
+        "1234",
+        constants::BARCODE_TYPE_ITF,
+        "1234",
+      ),
+      (
+        BarcodeSystem::Codabar,
+        "A40156B",
+        constants::BARCODE_TYPE_CODABAR,
+        "A40156B",
+      ),
+    ];
+
+    for (system, text, command, expected) in cases {
+      let mut builder = Barcode::builder();
+      builder.text(text).system(system);
+      let buffer = protocol.barcode(builder.build())?;
+
+      assert_eq!(buffer[12..15], *command);
+      assert_eq!(&buffer[15..buffer.len() - 1], expected.as_bytes());
+      assert_eq!(buffer[buffer.len() - 1], constants::NIL);
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_barcode_function_b() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let cases = [
+      (
+        BarcodeSystem::Code128,
+        "AB123456",
+        constants::BARCODE_TYPE_CODE128,
+        b"{BAB{C\x0C\x22\x38".to_vec(),
+      ),
+      (
+        BarcodeSystem::Code128,
+        "{B12",
+        constants::BARCODE_TYPE_CODE128,
+        b"{B12".to_vec(),
+      ),
+      (
+        BarcodeSystem::Gs1_128,
+        "(10)AB1(17)190508",
+        constants::BARCODE_TYPE_GS1_128,
+        b"10AB1{117190508".to_vec(),
+      ),
+    ];
+
+    for (system, text, command, expected) in cases {
+      let mut builder = Barcode::builder();
+      builder.text(text).system(system);
+      let buffer = protocol.barcode(builder.build())?;
+
+      assert_eq!(buffer[12..15], *command);
+      assert_eq!(buffer[15] as usize, expected.len());
+      assert_eq!(&buffer[16..], expected.as_slice());
+    }
+
+    let mut builder = Barcode::builder();
+    builder.text("A".repeat(256)).system(BarcodeSystem::Code128);
+    assert!(protocol.barcode(builder.build()).is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "graphics")]
+  fn test_barcode_raster() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = Barcode::builder();
+    builder
+      .text("9638507")
+      .system(BarcodeSystem::Ean8)
+      .width(2)
+      .height(40)
+      .text_position(BarcodeTextPosition::Below)
+      .raster(true);
+
+    let buffer = protocol.barcode(builder.build())?;
+
+    // 67 modules plus a quiet zone of 10 modules on either side, 2 dots each
+    let width: u16 = (67 + 20) * 2;
+    assert_eq!(&buffer[0..3], constants::IMAGE_HEADER);
+    assert_eq!(&buffer[6..8], &40u16.to_le_bytes());
+    assert_eq!(buffer.len(), 8 + usize::from((width + 7) / 8) * 40 + 15);
+    assert_eq!(
+      &buffer[buffer.len() - 15..],
+      [constants::FONT_A, b"96385074", constants::FONT_A, b"\n"].concat()
+    );
+
+    // the code set of explicit Code 128 data is not printed, module width 1 is allowed, and
+    // the text is printed in the barcode font before the font in use is restored
+    protocol.font(Font::C);
+    let mut builder = Barcode::builder();
+    builder
+      .text("{BABC{C1234")
+      .system(BarcodeSystem::Code128)
+      .width(1)
+      .text_position(BarcodeTextPosition::Above)
+      .font(BarcodeFont::B)
+      .raster(true);
+    let buffer = protocol.barcode(builder.build())?;
+    assert!(buffer.starts_with(&[constants::FONT_B, b"ABC1234", constants::FONT_C, b"\n"].concat()));
+
+    let mut builder = Barcode::builder();
+    builder.text("9638507").system(BarcodeSystem::Ean8).width(1);
+    assert!(protocol.barcode(builder.build()).is_err());
+
+    let mut builder = Barcode::builder();
+    builder
+      .text("0123450")
+      .system(BarcodeSystem::UpcE)
+      .raster(true);
+    assert!(protocol.barcode(builder.build()).is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_barcode_invalid() {
+    let mut protocol = Protocol::default();
+    let cases = [
+      (BarcodeSystem::UpcA, "0360002914"),
+      (BarcodeSystem::UpcA, "036000291453"),
+      (BarcodeSystem::UpcE, "03600029145"),
+      (BarcodeSystem::Ean13, "40063813339A"),
+      (BarcodeSystem::Ean8, "96385075"),
+      (BarcodeSystem::Code39, "code39"),
+      (BarcodeSystem::Itf, "12AB"),
+      (BarcodeSystem::Itf, "123"),
+      (BarcodeSystem::Codabar, "40156"),
+      (BarcodeSystem::Code93, "caf\u{e9}"),
+      (BarcodeSystem::DataBarOmnidirectional, "09501101020918"),
+      (BarcodeSystem::DataBarLimited, "2950110102091"),
+      (BarcodeSystem::DataBarExpanded, "09501101020917"),
+    ];
+
+    for (system, text) in cases {
+      let mut builder = Barcode::builder();
+      builder.text(text).system(system);
+      assert!(protocol.barcode(builder.build()).is_err());
+    }
+  }
+
+  #[test]
+  fn test_barcode_dimensions() -> Result<()> {
+    let mut protocol = Protocol::default();
+    let mut builder = Barcode::builder();
+    builder
+      .text("123456123456")
+      .system(BarcodeSystem::Ean13)
+      .width(4)
+      .height(50);
+    let barcode = builder.build();
+
+    let buffer = protocol.barcode(barcode)?;
+
+    assert_eq!(&buffer[0..2], constants::BARCODE_HEIGHT);
+    assert_eq!(buffer[2], 50);
+    assert_eq!(&buffer[3..5], constants::BARCODE_WIDTH);
+    assert_eq!(buffer[5], 4);
+
+    let invalid = [(1, 50), (7, 50), (3, 0)];
+    for (width, height) in invalid {
+      let mut builder = Barcode::builder();
+      builder
+        .text("123456123456")
+        .system(BarcodeSystem::Ean13)
+        .width(width)
+        .height(height);
+      assert!(protocol.barcode(builder.build()).is_err());
+    }
 
     Ok(())
   }
@@ -0,0 +1,861 @@
+use barcoders::sym::codabar::Codabar;
+use barcoders::sym::code128::Code128;
+use barcoders::sym::code39::Code39;
+use barcoders::sym::code93::Code93;
+use barcoders::sym::ean13::{EAN13, UPCA};
+use barcoders::sym::ean8::EAN8;
+use barcoders::sym::tf::TF;
+use std::fmt::Write;
+
+use crate::domain::{Barcode, BarcodeSystem};
+use crate::error::{PrinterError, Result};
+use crate::io::constants;
+
+/// Validates the barcode text and returns the `GS k` command together with the
+/// data the printer expects. The printer draws the bars itself, so the data is
+/// the human readable content (including the check digit where applicable).
+pub(crate) fn encode(barcode: &Barcode) -> Result<(&'static [u8], Vec<u8>)> {
+  let text = barcode.text();
+
+  match barcode.system() {
+    BarcodeSystem::UpcA => {
+      // barcoders treats UPC-A as an EAN-13 with a leading zero
+      UPCA::new(format!("0{text}")).map_err(|_| PrinterError::input("Invalid UPCA barcode"))?;
+      let data = check_digit(text, 11, "UPCA")?;
+      Ok((constants::BARCODE_TYPE_UPC_A, data.into_bytes()))
+    }
+    BarcodeSystem::UpcE => {
+      let data = upc_e(text)?;
+      Ok((constants::BARCODE_TYPE_UPC_E, data.into_bytes()))
+    }
+    BarcodeSystem::Ean13 => {
+      EAN13::new(text).map_err(|_| PrinterError::input("Invalid EAN13 barcode"))?;
+      let data = check_digit(text, 12, "EAN13")?;
+      Ok((constants::BARCODE_TYPE_JAN13_EAN13, data.into_bytes()))
+    }
+    BarcodeSystem::Ean8 => {
+      EAN8::new(text).map_err(|_| PrinterError::input("Invalid EAN8 barcode"))?;
+      let data = check_digit(text, 7, "EAN8")?;
+      Ok((constants::BARCODE_TYPE_JAN8_EAN8, data.into_bytes()))
+    }
+    BarcodeSystem::Code39 => {
+      // the start and stop characters are optional, the printer adds them if missing
+      let content = text.strip_prefix('*').unwrap_or(text);
+      let content = content.strip_suffix('*').unwrap_or(content);
+      Code39::new(content).map_err(|_| PrinterError::input("Invalid Code39 barcode"))?;
+      Ok((constants::BARCODE_TYPE_CODE39, text.as_bytes().to_vec()))
+    }
+    BarcodeSystem::Itf => {
+      TF::interleaved(text).map_err(|_| PrinterError::input("Invalid ITF barcode"))?;
+      // interleaved 2 of 5 encodes digit pairs, a check digit has to be part of the text
+      if text.len() % 2 == 1 {
+        return Err(PrinterError::input(
+          "Invalid ITF barcode, expected an even number of digits",
+        ));
+      }
+      Ok((constants::BARCODE_TYPE_ITF, text.as_bytes().to_vec()))
+    }
+    BarcodeSystem::Codabar => {
+      Codabar::new(text).map_err(|_| PrinterError::input("Invalid Codabar barcode"))?;
+      let is_guard = |c: Option<char>| matches!(c, Some('A'..='D'));
+      if text.len() < 2 || !is_guard(text.chars().next()) || !is_guard(text.chars().last()) {
+        return Err(PrinterError::input(
+          "Invalid Codabar barcode, it must start and end with one of A, B, C or D",
+        ));
+      }
+      Ok((constants::BARCODE_TYPE_CODABAR, text.as_bytes().to_vec()))
+    }
+    BarcodeSystem::Code128 => {
+      let data = if text.starts_with("{A") || text.starts_with("{B") || text.starts_with("{C") {
+        code128_explicit(text)?
+      } else {
+        code128_auto(text)?
+      };
+      Ok((constants::BARCODE_TYPE_CODE128, data))
+    }
+    BarcodeSystem::Gs1_128 => {
+      let data = gs1_128(text)?;
+      Ok((constants::BARCODE_TYPE_GS1_128, data))
+    }
+    BarcodeSystem::Code93 => {
+      if text.is_empty() || !text.is_ascii() {
+        return Err(PrinterError::input(
+          "Invalid Code93 barcode, only ASCII characters are allowed",
+        ));
+      }
+      Ok((constants::BARCODE_TYPE_CODE93, text.as_bytes().to_vec()))
+    }
+    BarcodeSystem::DataBarOmnidirectional => {
+      let data = databar_gtin(text, "GS1 DataBar Omnidirectional")?;
+      Ok((constants::BARCODE_TYPE_GS1_DATABAR_OMNIDIRECTIONAL, data))
+    }
+    BarcodeSystem::DataBarTruncated => {
+      let data = databar_gtin(text, "GS1 DataBar Truncated")?;
+      Ok((constants::BARCODE_TYPE_GS1_DATABAR_TRUNCATED, data))
+    }
+    BarcodeSystem::DataBarLimited => {
+      let data = databar_gtin(text, "GS1 DataBar Limited")?;
+      if data[0] > b'1' {
+        return Err(PrinterError::input(
+          "Invalid GS1 DataBar Limited barcode, the first digit must be 0 or 1",
+        ));
+      }
+      Ok((constants::BARCODE_TYPE_GS1_DATABAR_LIMITED, data))
+    }
+    BarcodeSystem::DataBarExpanded => {
+      let data = gs1_128(text)?;
+      Ok((constants::BARCODE_TYPE_GS1_DATABAR_EXPANDED, data))
+    }
+  }
+}
+
+/// Encodes the barcode as a pattern of bar modules (1 is a bar, 0 is a space) for
+/// printing it as a graphic. The data is normalised in the same way as [`encode`].
+pub(crate) fn modules(barcode: &Barcode) -> Result<Vec<u8>> {
+  let (_, data) = encode(barcode)?;
+  let unsupported = || {
+    PrinterError::input(format!(
+      "{} barcodes cannot be rasterised",
+      barcode.system()
+    ))
+  };
+  let invalid = |_| PrinterError::input(format!("Invalid {} barcode", barcode.system()));
+  let text = String::from_utf8_lossy(&data);
+
+  let modules = match barcode.system() {
+    // barcoders calculates the check digits itself
+    BarcodeSystem::UpcA => UPCA::new(format!("0{}", &text[..11]))
+      .map_err(invalid)?
+      .encode(),
+    BarcodeSystem::Ean13 => EAN13::new(&text[..12]).map_err(invalid)?.encode(),
+    BarcodeSystem::Ean8 => EAN8::new(&text[..7]).map_err(invalid)?.encode(),
+    BarcodeSystem::Code39 => Code39::new(text.trim_matches('*'))
+      .map_err(invalid)?
+      .encode(),
+    BarcodeSystem::Itf => TF::interleaved(text).map_err(invalid)?.encode(),
+    BarcodeSystem::Codabar => Codabar::new(text).map_err(invalid)?.encode(),
+    BarcodeSystem::Code93 => Code93::new(text).map_err(invalid)?.encode(),
+    BarcodeSystem::Code128 => Code128::new(barcoders_code128(&data))
+      .map_err(invalid)?
+      .encode(),
+    BarcodeSystem::Gs1_128 => {
+      // GS1-128 is Code 128 with FNC1 after the start character and as separator. The
+      // elements are encoded as one symbol, so the code set carries over between them.
+      let mut code128 = Vec::new();
+      let mut set = None;
+      for (index, element) in data.split(|c| *c == b'{').enumerate() {
+        let element = if index == 0 {
+          element
+        } else {
+          // drop the `1` of the `{1` separator
+          code128.extend_from_slice(constants::CODE128_FNC1);
+          &element[1..]
+        };
+        let (mut encoded, next) = code128_auto_from(&String::from_utf8_lossy(element), set)?;
+        if index == 0 {
+          encoded.splice(2..2, constants::CODE128_FNC1.iter().copied());
+        }
+        code128.extend(encoded);
+        set = next;
+      }
+      Code128::new(barcoders_code128(&code128))
+        .map_err(invalid)?
+        .encode()
+    }
+    BarcodeSystem::UpcE
+    | BarcodeSystem::DataBarOmnidirectional
+    | BarcodeSystem::DataBarTruncated
+    | BarcodeSystem::DataBarLimited
+    | BarcodeSystem::DataBarExpanded => return Err(unsupported()),
+  };
+
+  Ok(modules)
+}
+
+/// The human readable text printed under a rasterised barcode. Code 128 data is decoded,
+/// dropping the code set and function characters.
+pub(crate) fn hri(barcode: &Barcode) -> Result<Vec<u8>> {
+  let (_, data) = encode(barcode)?;
+  let text = match barcode.system() {
+    BarcodeSystem::Code128 => code128_hri(&data),
+    BarcodeSystem::Gs1_128 => barcode.text().as_bytes().to_vec(),
+    _ => data,
+  };
+  Ok(text)
+}
+
+fn code128_hri(data: &[u8]) -> Vec<u8> {
+  let mut text = Vec::new();
+  let mut set = CodeSet::B;
+  let mut i = 0;
+
+  while i < data.len() {
+    if data[i] == b'{' && i + 1 < data.len() {
+      match data[i + 1] {
+        b'A' => set = CodeSet::A,
+        b'B' => set = CodeSet::B,
+        b'C' => set = CodeSet::C,
+        b'{' => text.push(b'{'),
+        _ => {}
+      }
+      i += 2;
+    } else {
+      match set {
+        CodeSet::C => text.extend(format!("{:02}", data[i]).bytes()),
+        // control characters of code set A are not printable
+        _ if data[i] < 0x20 || data[i] == 127 => {}
+        _ => text.push(data[i]),
+      }
+      i += 1;
+    }
+  }
+
+  text
+}
+
+/// Converts Code 128 printer data into the notation used by barcoders, which marks
+/// the code sets and function characters with unicode characters.
+/// A code set selected again while it is in use is dropped, as barcoders rejects it.
+fn barcoders_code128(data: &[u8]) -> String {
+  let mut text = String::new();
+  let mut set: Option<CodeSet> = None;
+  let mut i = 0;
+
+  while i < data.len() {
+    if data[i] == b'{' && i + 1 < data.len() {
+      let mut select = |next: CodeSet, marker: char| {
+        if set != Some(next) {
+          set = Some(next);
+          text.push(marker);
+        }
+      };
+      match data[i + 1] {
+        b'A' => select(CodeSet::A, '\u{00C0}'),
+        b'B' => select(CodeSet::B, '\u{0181}'),
+        b'C' => select(CodeSet::C, '\u{0106}'),
+        b'1' => text.push('\u{0179}'),
+        b'2' => text.push('\u{017A}'),
+        b'3' => text.push('\u{017B}'),
+        b'4' => text.push('\u{017C}'),
+        b'S' => text.push('\u{017D}'),
+        other => text.push(char::from(other)),
+      }
+      i += 2;
+    } else {
+      match set {
+        Some(CodeSet::C) => {
+          let _ = write!(text, "{:02}", data[i]);
+        }
+        Some(CodeSet::B) | None if data[i] == 127 => text.push('\u{00F7}'),
+        _ => text.push(char::from(data[i])),
+      }
+      i += 1;
+    }
+  }
+
+  text
+}
+
+/// Appends the check digit when the text has `length` digits, or verifies it
+/// when the text already includes one.
+fn check_digit(text: &str, length: usize, name: &str) -> Result<String> {
+  if text.len() == length {
+    Ok(format!("{text}{}", modulo_10(text)))
+  } else if text.len() == length + 1 {
+    let (digits, check) = text.split_at(length);
+    if check == modulo_10(digits).to_string() {
+      Ok(text.to_string())
+    } else {
+      Err(PrinterError::input(format!(
+        "Invalid {name} barcode, check digit should be {}",
+        modulo_10(digits)
+      )))
+    }
+  } else {
+    Err(PrinterError::input(format!(
+      "Invalid {name} barcode, expected {length} or {} digits",
+      length + 1
+    )))
+  }
+}
+
+/// Normalises UPC-E input to the 8 digit form (number system, 6 digits and check digit).
+/// Accepts 6, 7 or 8 digit UPC-E numbers, or 11 and 12 digit UPC-A numbers that can be
+/// zero-suppressed.
+fn upc_e(text: &str) -> Result<String> {
+  if !text.chars().all(|c| c.is_ascii_digit()) {
+    return Err(PrinterError::input(
+      "Invalid UPCE barcode, only digits are allowed",
+    ));
+  }
+
+  let (number_system, digits, check) = match text.len() {
+    6 => ("0", text, None),
+    7 => (&text[..1], &text[1..], None),
+    8 => (&text[..1], &text[1..7], Some(&text[7..])),
+    11 | 12 => {
+      let upc_a = check_digit(text, 11, "UPCE")?;
+      let compressed = upc_a_to_upc_e(&upc_a[..11]).ok_or_else(|| {
+        PrinterError::input(format!("UPC-A number {upc_a} cannot be converted to UPC-E"))
+      })?;
+      return Ok(format!("{compressed}{}", &upc_a[11..]));
+    }
+    _ => {
+      return Err(PrinterError::input(
+        "Invalid UPCE barcode, expected 6, 7, 8, 11 or 12 digits",
+      ))
+    }
+  };
+
+  if number_system != "0" && number_system != "1" {
+    return Err(PrinterError::input(
+      "Invalid UPCE barcode, the number system must be 0 or 1",
+    ));
+  }
+
+  let upc_a = upc_e_to_upc_a(number_system, digits);
+  let expected = modulo_10(&upc_a).to_string();
+  match check {
+    Some(check) if check != expected => Err(PrinterError::input(format!(
+      "Invalid UPCE barcode, check digit should be {expected}"
+    ))),
+    _ => Ok(format!("{number_system}{digits}{expected}")),
+  }
+}
+
+/// Expands the 6 UPC-E digits to the 11 digit UPC-A number (without check digit).
+fn upc_e_to_upc_a(number_system: &str, digits: &str) -> String {
+  let d = digits.as_bytes();
+  let s = |range: std::ops::Range<usize>| &digits[range];
+  match d[5] {
+    b'0'..=b'2' => format!("{number_system}{}{}0000{}", s(0..2), s(5..6), s(2..5)),
+    b'3' => format!("{number_system}{}00000{}", s(0..3), s(3..5)),
+    b'4' => format!("{number_system}{}00000{}", s(0..4), s(4..5)),
+    _ => format!("{number_system}{}0000{}", s(0..5), s(5..6)),
+  }
+}
+
+/// Applies the zero-suppression rules to an 11 digit UPC-A number, returning the
+/// number system and the 6 UPC-E digits, or `None` when it cannot be compressed.
+fn upc_a_to_upc_e(upc_a: &str) -> Option<String> {
+  let number_system = &upc_a[..1];
+  let manufacturer = &upc_a[1..6];
+  let product = &upc_a[6..11];
+
+  if number_system != "0" && number_system != "1" {
+    return None;
+  }
+
+  let m = manufacturer.as_bytes();
+  let p = product.as_bytes();
+
+  let digits = if manufacturer.ends_with("00") && m[2] <= b'2' && product.starts_with("00") {
+    format!(
+      "{}{}{}",
+      &manufacturer[..2],
+      &product[2..],
+      &manufacturer[2..3]
+    )
+  } else if manufacturer.ends_with("00") && product.starts_with("000") {
+    format!("{}{}3", &manufacturer[..3], &product[3..])
+  } else if manufacturer.ends_with('0') && product.starts_with("0000") {
+    format!("{}{}4", &manufacturer[..4], &product[4..])
+  } else if product.starts_with("0000") && p[4] >= b'5' {
+    format!("{manufacturer}{}", &product[4..])
+  } else {
+    return None;
+  };
+
+  Some(format!("{number_system}{digits}"))
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum CodeSet {
+  A,
+  B,
+  C,
+}
+
+impl CodeSet {
+  fn prefix(self) -> &'static [u8] {
+    match self {
+      CodeSet::A => constants::CODE128_CODE_SET_A,
+      CodeSet::B => constants::CODE128_CODE_SET_B,
+      CodeSet::C => constants::CODE128_CODE_SET_C,
+    }
+  }
+
+  fn contains(self, c: u8) -> bool {
+    match self {
+      CodeSet::A => c < 96,
+      CodeSet::B => (32..128).contains(&c),
+      CodeSet::C => c.is_ascii_digit(),
+    }
+  }
+}
+
+/// Validates Code 128 data that selects its code sets with the `{A`, `{B` and `{C`
+/// prefixes. Digits in code set C are packed in pairs, as expected by the printer.
+fn code128_explicit(text: &str) -> Result<Vec<u8>> {
+  let bytes = text.as_bytes();
+  let mut data = Vec::new();
+  let mut set = CodeSet::B;
+  let mut i = 0;
+
+  while i < bytes.len() {
+    if bytes[i] == b'{' {
+      let code = bytes
+        .get(i + 1)
+        .ok_or_else(|| PrinterError::input("Invalid Code128 barcode, incomplete special code"))?;
+      match code {
+        b'A' => set = CodeSet::A,
+        b'B' => set = CodeSet::B,
+        b'C' => set = CodeSet::C,
+        b'1' | b'2' | b'3' | b'4' | b'S' | b'{' => {}
+        _ => {
+          return Err(PrinterError::input(format!(
+            "Invalid Code128 barcode, unknown special code {{{}",
+            char::from(*code)
+          )))
+        }
+      }
+      data.extend_from_slice(&bytes[i..i + 2]);
+      i += 2;
+    } else if set == CodeSet::C {
+      match bytes.get(i..i + 2) {
+        Some([a, b]) if a.is_ascii_digit() && b.is_ascii_digit() => {
+          data.push((a - b'0') * 10 + (b - b'0'));
+          i += 2;
+        }
+        _ => {
+          return Err(PrinterError::input(
+            "Invalid Code128 barcode, code set C only accepts pairs of digits",
+          ))
+        }
+      }
+    } else if set.contains(bytes[i]) {
+      data.push(bytes[i]);
+      i += 1;
+    } else {
+      return Err(PrinterError::input(format!(
+        "Invalid Code128 barcode, character {} is not in the selected code set",
+        char::from(bytes[i])
+      )));
+    }
+  }
+
+  Ok(data)
+}
+
+/// Encodes plain text as Code 128, choosing the code sets to keep the symbol short.
+/// Runs of digits use code set C, control characters code set A and everything else
+/// code set B.
+fn code128_auto(text: &str) -> Result<Vec<u8>> {
+  code128_auto_from(text, None).map(|(data, _)| data)
+}
+
+/// Encodes text that continues a Code 128 symbol already in the code set `set`, so the
+/// code set is only switched when it changes. Returns the code set at the end of the text.
+fn code128_auto_from(text: &str, set: Option<CodeSet>) -> Result<(Vec<u8>, Option<CodeSet>)> {
+  if text.is_empty() || !text.is_ascii() {
+    return Err(PrinterError::input(
+      "Invalid Code128 barcode, only ASCII characters are allowed",
+    ));
+  }
+
+  let bytes = text.as_bytes();
+  let mut data = Vec::new();
+  let mut set = set;
+  let mut i = 0;
+
+  while i < bytes.len() {
+    let mut digits = bytes[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+    let at_end = i + digits == bytes.len();
+    let use_c = if set.is_none() {
+      digits >= 4 || (at_end && digits >= 2 && digits % 2 == 0)
+    } else {
+      digits >= 6 || (at_end && digits >= 4)
+    };
+
+    if use_c {
+      if digits % 2 == 1 {
+        // the odd digit is written in the current code set before switching
+        if set.is_none() || set == Some(CodeSet::C) {
+          data.extend_from_slice(constants::CODE128_CODE_SET_B);
+          set = Some(CodeSet::B);
+        }
+        data.push(bytes[i]);
+        i += 1;
+        digits -= 1;
+      }
+      if set != Some(CodeSet::C) {
+        data.extend_from_slice(constants::CODE128_CODE_SET_C);
+        set = Some(CodeSet::C);
+      }
+      for pair in bytes[i..i + digits].chunks(2) {
+        data.push((pair[0] - b'0') * 10 + (pair[1] - b'0'));
+      }
+      i += digits;
+      continue;
+    }
+
+    let c = bytes[i];
+    let next = match set {
+      Some(current) if current != CodeSet::C && current.contains(c) => current,
+      _ => {
+        // pick the code set of the first character that only exists in one of them
+        let control = bytes[i..].iter().find(|c| **c < 32 || **c >= 96);
+        match control {
+          Some(c) if *c < 32 => CodeSet::A,
+          _ => CodeSet::B,
+        }
+      }
+    };
+
+    if set != Some(next) {
+      data.extend_from_slice(next.prefix());
+      set = Some(next);
+    }
+
+    if c == b'{' {
+      data.extend_from_slice(constants::CODE128_BRACE);
+    } else {
+      data.push(c);
+    }
+    i += 1;
+  }
+
+  Ok((data, set))
+}
+
+/// Application identifiers with a predefined length, keyed by their first two digits.
+/// Values of any other identifier are variable length and need a FNC1 separator.
+const GS1_PREDEFINED_LENGTHS: &[(&str, usize)] = &[
+  ("00", 18),
+  ("01", 14),
+  ("02", 14),
+  ("03", 14),
+  ("04", 16),
+  ("11", 6),
+  ("12", 6),
+  ("13", 6),
+  ("14", 6),
+  ("15", 6),
+  ("16", 6),
+  ("17", 6),
+  ("18", 6),
+  ("19", 6),
+  ("20", 2),
+  ("31", 6),
+  ("32", 6),
+  ("33", 6),
+  ("34", 6),
+  ("35", 6),
+  ("36", 6),
+  ("41", 13),
+];
+
+/// Parses `(AI)value` pairs into a GS1 element string. The parentheses are only
+/// used to delimit the application identifiers and are not encoded.
+fn gs1_elements(text: &str) -> Result<Vec<(&str, &str, bool)>> {
+  let invalid = |reason: &str| PrinterError::input(format!("Invalid GS1 barcode, {reason}"));
+
+  let mut elements = Vec::new();
+  let mut rest = text;
+
+  while !rest.is_empty() {
+    let inner = rest
+      .strip_prefix('(')
+      .ok_or_else(|| invalid("expected an application identifier in parentheses"))?;
+    let end = inner
+      .find(')')
+      .ok_or_else(|| invalid("missing closing parenthesis"))?;
+    let ai = &inner[..end];
+    let inner = &inner[end + 1..];
+    let value_end = inner.find('(').unwrap_or(inner.len());
+    let value = &inner[..value_end];
+    rest = &inner[value_end..];
+
+    if !(2..=4).contains(&ai.len()) || !ai.chars().all(|c| c.is_ascii_digit()) {
+      return Err(invalid(&format!("{ai} is not an application identifier")));
+    }
+
+    if value.is_empty() || !value.bytes().all(is_gs1_character) {
+      return Err(invalid(&format!(
+        "invalid value for application identifier {ai}"
+      )));
+    }
+
+    let predefined = GS1_PREDEFINED_LENGTHS
+      .iter()
+      .find(|(prefix, _)| ai.starts_with(prefix))
+      .map(|(_, length)| *length);
+
+    if let Some(length) = predefined {
+      // 4 digit identifiers such as 3103 carry a decimal point indicator
+      let ai_length = match &ai[..2] {
+        "31" | "32" | "33" | "34" | "35" | "36" => 4,
+        "41" => 3,
+        _ => 2,
+      };
+      if ai.len() != ai_length {
+        return Err(invalid(&format!("{ai} is not an application identifier")));
+      }
+      if value.len() != length || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid(&format!(
+          "application identifier {ai} requires {length} digits"
+        )));
+      }
+      if matches!(ai, "00" | "01" | "02") {
+        let (digits, check) = value.split_at(length - 1);
+        if check != modulo_10(digits).to_string() {
+          return Err(invalid(&format!(
+            "check digit of application identifier {ai} should be {}",
+            modulo_10(digits)
+          )));
+        }
+      }
+    }
+
+    elements.push((ai, value, predefined.is_none()));
+  }
+
+  if elements.is_empty() {
+    return Err(invalid("no application identifiers provided"));
+  }
+
+  Ok(elements)
+}
+
+/// Builds the GS1-128 and GS1 DataBar Expanded data for the printer, which adds the
+/// leading FNC1 itself.
+/// Variable length values are terminated with FNC1 unless they are the last element.
+fn gs1_128(text: &str) -> Result<Vec<u8>> {
+  let elements = gs1_elements(text)?;
+  let mut data = Vec::new();
+
+  for (index, (ai, value, variable)) in elements.iter().enumerate() {
+    data.extend_from_slice(ai.as_bytes());
+    data.extend_from_slice(value.as_bytes());
+    if *variable && index < elements.len() - 1 {
+      data.extend_from_slice(constants::CODE128_FNC1);
+    }
+  }
+
+  Ok(data)
+}
+
+/// Validates the GTIN of the fixed length GS1 DataBar symbols. The printer adds the
+/// (01) application identifier and the check digit, so only 13 digits are sent.
+fn databar_gtin(text: &str, name: &str) -> Result<Vec<u8>> {
+  if !text.chars().all(|c| c.is_ascii_digit()) {
+    return Err(PrinterError::input(format!(
+      "Invalid {name} barcode, only digits are allowed"
+    )));
+  }
+  let gtin = check_digit(text, 13, name)?;
+  Ok(gtin.as_bytes()[..13].to_vec())
+}
+
+/// GS1 element strings are limited to a subset of ASCII (character set 82).
+fn is_gs1_character(c: u8) -> bool {
+  c.is_ascii_alphanumeric() || b"!\"%&'()*+,-./:;<=>?_".contains(&c)
+}
+
+/// Calculates the modulo 10 check digit used by the UPC and EAN systems.
+/// The weighting starts at 3 from the rightmost digit.
+pub(crate) fn modulo_10(digits: &str) -> u32 {
+  let sum: u32 = digits
+    .chars()
+    .rev()
+    .filter_map(|c| c.to_digit(10))
+    .enumerate()
+    .map(|(i, d)| if i % 2 == 0 { d * 3 } else { d })
+    .sum();
+  (10 - sum % 10) % 10
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_modulo_10() {
+    assert_eq!(modulo_10("03600029145"), 2);
+    assert_eq!(modulo_10("400638133393"), 1);
+    assert_eq!(modulo_10("9638507"), 4);
+    assert_eq!(modulo_10("123456123456"), 0);
+  }
+
+  #[test]
+  fn test_check_digit() -> Result<()> {
+    assert_eq!(check_digit("03600029145", 11, "UPCA")?, "036000291452");
+    assert_eq!(check_digit("036000291452", 11, "UPCA")?, "036000291452");
+    assert!(check_digit("036000291453", 11, "UPCA").is_err());
+    assert!(check_digit("0360002914", 11, "UPCA").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_upc_e_expansion() {
+    assert_eq!(upc_e_to_upc_a("0", "123450"), "01200000345");
+    assert_eq!(upc_e_to_upc_a("0", "123451"), "01210000345");
+    assert_eq!(upc_e_to_upc_a("0", "123452"), "01220000345");
+    assert_eq!(upc_e_to_upc_a("0", "123453"), "01230000045");
+    assert_eq!(upc_e_to_upc_a("0", "123454"), "01234000005");
+    assert_eq!(upc_e_to_upc_a("0", "123455"), "01234500005");
+    assert_eq!(upc_e_to_upc_a("1", "654329"), "16543200009");
+  }
+
+  #[test]
+  fn test_upc_e_compression() {
+    // manufacturer ending in 000, 100 or 200 with a product code up to 00999
+    assert_eq!(upc_a_to_upc_e("01200000345").as_deref(), Some("0123450"));
+    assert_eq!(upc_a_to_upc_e("01210000345").as_deref(), Some("0123451"));
+    assert_eq!(upc_a_to_upc_e("01220000345").as_deref(), Some("0123452"));
+    // manufacturer ending in 00 with a product code up to 00099
+    assert_eq!(upc_a_to_upc_e("01230000045").as_deref(), Some("0123453"));
+    // manufacturer ending in 0 with a product code up to 00009
+    assert_eq!(upc_a_to_upc_e("01234000005").as_deref(), Some("0123454"));
+    // any other manufacturer with a product code between 00005 and 00009
+    assert_eq!(upc_a_to_upc_e("01234500005").as_deref(), Some("0123455"));
+    assert_eq!(upc_a_to_upc_e("01234500009").as_deref(), Some("0123459"));
+
+    assert_eq!(upc_a_to_upc_e("01230000145"), None);
+    assert_eq!(upc_a_to_upc_e("01234000015"), None);
+    assert_eq!(upc_a_to_upc_e("01234500004"), None);
+    assert_eq!(upc_a_to_upc_e("21200000345"), None);
+  }
+
+  #[test]
+  fn test_upc_e() -> Result<()> {
+    assert_eq!(upc_e("123450")?, "01234505");
+    assert_eq!(upc_e("0123450")?, "01234505");
+    assert_eq!(upc_e("01234505")?, "01234505");
+    assert_eq!(upc_e("01200000345")?, "01234505");
+    assert_eq!(upc_e("012000003455")?, "01234505");
+    assert_eq!(upc_e("1654329")?, "16543294");
+
+    assert!(upc_e("01234506").is_err());
+    assert!(upc_e("2123450").is_err());
+    assert!(upc_e("03600029145").is_err());
+    assert!(upc_e("12345").is_err());
+    assert!(upc_e("12345A").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_code128_auto() -> Result<()> {
+    assert_eq!(code128_auto("123456")?, b"{C\x0C\x22\x38");
+    assert_eq!(code128_auto("ABC123")?, b"{BABC123");
+    assert_eq!(code128_auto("AB123456")?, b"{BAB{C\x0C\x22\x38");
+    assert_eq!(code128_auto("12345")?, b"{B1{C\x17\x2D");
+    assert_eq!(code128_auto("abc{")?, b"{Babc{{");
+    assert_eq!(code128_auto("A\tB")?, b"{AA\tB");
+    assert_eq!(code128_auto("x\ty")?, b"{Bx{A\t{By");
+
+    assert!(code128_auto("").is_err());
+    assert!(code128_auto("caf\u{e9}").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_code128_explicit() -> Result<()> {
+    assert_eq!(code128_explicit("{C123456")?, b"{C\x0C\x22\x38");
+    assert_eq!(code128_explicit("{BABC{C1234")?, b"{BABC{C\x0C\x22");
+    assert_eq!(code128_explicit("{AABC{1DEF")?, b"{AABC{1DEF");
+
+    assert!(code128_explicit("{C123").is_err());
+    assert!(code128_explicit("{Aabc").is_err());
+    assert!(code128_explicit("{BABC{X").is_err());
+    assert!(code128_explicit("{BABC{").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_gs1_128() -> Result<()> {
+    assert_eq!(
+      gs1_128("(01)09501101020917(17)190508(10)ABC123")?,
+      b"01095011010209171719050810ABC123"
+    );
+    assert_eq!(gs1_128("(10)ABC123(17)190508")?, b"10ABC123{117190508");
+    assert_eq!(gs1_128("(3103)000250")?, b"3103000250");
+
+    assert!(gs1_128("").is_err());
+    assert!(gs1_128("0109501101020917").is_err());
+    assert!(gs1_128("(01)0950110102091").is_err());
+    assert!(gs1_128("(01)09501101020918").is_err());
+    assert!(gs1_128("(310)000250").is_err());
+    assert!(gs1_128("(10)").is_err());
+    assert!(gs1_128("(10)ABC{123").is_err());
+    assert!(gs1_128("(1A)ABC").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_databar_gtin() -> Result<()> {
+    assert_eq!(databar_gtin("0950110102091", "DataBar")?, b"0950110102091");
+    assert_eq!(databar_gtin("09501101020917", "DataBar")?, b"0950110102091");
+
+    assert!(databar_gtin("09501101020918", "DataBar").is_err());
+    assert!(databar_gtin("095011010209", "DataBar").is_err());
+    assert!(databar_gtin("095011010209A", "DataBar").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_barcoders_code128() {
+    assert_eq!(
+      barcoders_code128(b"{BAB{C\x0C\x22"),
+      "\u{0181}AB\u{0106}1234"
+    );
+    assert_eq!(barcoders_code128(b"{A{1AB{{"), "\u{00C0}\u{0179}AB{");
+    // a code set selected again while in use is dropped
+    assert_eq!(barcoders_code128(b"{C\x0C{C\x22"), "\u{0106}1234");
+  }
+
+  #[test]
+  fn test_code128_hri() {
+    assert_eq!(code128_hri(b"{BABC{C\x0C\x22"), b"ABC1234");
+    assert_eq!(code128_hri(b"{A{1AB{{\t"), b"AB{");
+    assert_eq!(code128_hri(b"{Bx{A\t{By"), b"xy");
+  }
+
+  #[test]
+  fn test_modules() -> Result<()> {
+    let mut builder = Barcode::builder();
+    builder.system(BarcodeSystem::Ean8).text("9638507");
+    let bars = modules(&builder.build())?;
+    // 3 + 4 * 7 + 5 + 4 * 7 + 3
+    assert_eq!(bars.len(), 67);
+    assert_eq!(&bars[0..3], &[1, 0, 1]);
+
+    let mut builder = Barcode::builder();
+    builder.system(BarcodeSystem::Code128).text("AB1234");
+    // start, 2 characters, switch, 2 pairs, checksum and stop with termination bar
+    assert_eq!(modules(&builder.build())?.len(), 6 * 11 + 11 + 13);
+
+    let mut builder = Barcode::builder();
+    builder
+      .system(BarcodeSystem::Gs1_128)
+      .text("(01)09501101020917(10)AB");
+    assert!(!modules(&builder.build())?.is_empty());
+
+    // a numeric element followed by another numeric element stays in code set C
+    let mut builder = Barcode::builder();
+    builder
+      .system(BarcodeSystem::Gs1_128)
+      .text("(10)1234(17)190508");
+    // start, FNC1, 3 pairs, FNC1, 4 pairs, checksum and stop with termination bar
+    assert_eq!(modules(&builder.build())?.len(), 11 * 11 + 13);
+
+    let mut builder = Barcode::builder();
+    builder.system(BarcodeSystem::Code128).text("{C12{C34");
+    assert!(!modules(&builder.build())?.is_empty());
+
+    let mut builder = Barcode::builder();
+    builder.system(BarcodeSystem::UpcE).text("0123450");
+    assert!(modules(&builder.build()).is_err());
+    Ok(())
+  }
+}
@@ -12,11 +12,15 @@
 
 extern crate core;
 
+#[cfg(feature = "tokio")]
+pub use async_printer::*;
 pub use domain::*;
 pub use error::*;
 pub use io::*;
 pub use printer::*;
 
+#[cfg(feature = "tokio")]
+mod async_printer;
 mod domain;
 mod error;
 mod io;
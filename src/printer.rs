@@ -1,18 +1,35 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
 use log::debug;
 
-use crate::domain::{Alignment, BarcodeBuilder, Font, Hardware, Qr, QrBuilder, UnderlineMode};
+use crate::domain::{
+  Alignment, AsbReport, Aztec, AztecBuilder, BarcodeBuilder, Capabilities, DataMatrix,
+  DataMatrixBuilder, Font, GraphicSize, Hardware, Pdf417, Pdf417Builder, PrinterEvent, Qr,
+  QrBuilder, Status, StatusKind, UnderlineMode,
+};
 #[cfg(feature = "graphics")]
 use crate::domain::{Graphic, GraphicBuilder};
-use crate::error::Result;
-use crate::io::{Driver, Encoder, NoopDriver, Protocol};
+#[cfg(feature = "fonts")]
+use crate::domain::{TextImage, TextImageBuilder};
+use crate::error::{PrinterError, Result};
+#[cfg(feature = "tokio")]
+use crate::io::AsyncDriver;
+use crate::io::{constants, AsbListener, Driver, Encoder, NoopDriver, Protocol, Replies};
+#[cfg(feature = "tokio")]
+use crate::AsyncPrinter;
 use crate::Barcode;
 
-pub struct Printer(Box<dyn Driver>, Protocol);
+// how long to wait for the printer to reply to a request
+pub(crate) const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+// the last field holds the replies passed on while a listener is running
+pub struct Printer(Arc<dyn Driver>, Protocol, Weak<Replies>);
 
 impl Printer {
   fn new(driver: Box<dyn Driver>, protocol: Protocol) -> Self {
     debug!("Creating printer with driver: {}", driver.name());
-    Self(driver, protocol)
+    Self(Arc::from(driver), protocol, Weak::new())
   }
 
   pub fn open(driver: Box<dyn Driver>) -> Result<Self> {
@@ -174,6 +191,42 @@ impl Printer {
     self.0.write(&bytes).map(|_| self)
   }
 
+  pub fn pdf417<F>(&mut self, function: F) -> Result<&mut Self>
+  where
+    F: Fn(&mut Pdf417Builder) -> &mut Pdf417Builder,
+  {
+    let mut builder = Pdf417::builder();
+    function(&mut builder);
+    let pdf417 = builder.build();
+    debug!("Printing pdf417: {}", pdf417);
+    let bytes = self.1.pdf417(pdf417)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  pub fn data_matrix<F>(&mut self, function: F) -> Result<&mut Self>
+  where
+    F: Fn(&mut DataMatrixBuilder) -> &mut DataMatrixBuilder,
+  {
+    let mut builder = DataMatrix::builder();
+    function(&mut builder);
+    let data_matrix = builder.build();
+    debug!("Printing data matrix: {}", data_matrix);
+    let bytes = self.1.data_matrix(data_matrix)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  pub fn aztec<F>(&mut self, function: F) -> Result<&mut Self>
+  where
+    F: Fn(&mut AztecBuilder) -> &mut AztecBuilder,
+  {
+    let mut builder = Aztec::builder();
+    function(&mut builder);
+    let aztec = builder.build();
+    debug!("Printing aztec: {}", aztec);
+    let bytes = self.1.aztec(aztec)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
   #[cfg(feature = "graphics")]
   pub fn graphic<F>(&mut self, function: F) -> Result<&mut Self>
   where
@@ -182,11 +235,146 @@ impl Printer {
     let mut builder = Graphic::builder();
     function(&mut builder);
     let graphic = builder.build()?;
-    debug!("Printing graphic: {}", graphic.path());
+    debug!(
+      "Printing graphic: {}",
+      graphic.path().unwrap_or("in-memory image")
+    );
     let bytes = self.1.graphic(graphic)?;
     self.0.write(&bytes).map(|_| self)
   }
 
+  /// Renders text with a TrueType or OpenType font and prints it as a graphic
+  #[cfg(feature = "fonts")]
+  pub fn text_image<F>(&mut self, function: F) -> Result<&mut Self>
+  where
+    F: Fn(&mut TextImageBuilder) -> &mut TextImageBuilder,
+  {
+    let mut builder = TextImage::builder();
+    function(&mut builder);
+    let text_image = builder.build()?;
+    debug!("Printing text image: {}", text_image);
+    let bytes = self.1.graphic(text_image.graphic()?)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  /// Prints lines of right-to-left or complex script text (such as Arabic or Hebrew) as
+  /// graphics rendered with the configured font. Other lines are still sent as text.
+  #[cfg(feature = "fonts")]
+  pub fn complex_text<F>(&mut self, function: F) -> Result<&mut Self>
+  where
+    F: Fn(&mut TextImageBuilder) -> &mut TextImageBuilder,
+  {
+    let mut builder = TextImage::builder();
+    function(&mut builder);
+    let text_image = builder.build()?;
+    debug!("Enabling complex text: {}", text_image);
+    self.1.set_complex_text(Some(text_image));
+    Ok(self)
+  }
+
+  #[cfg(feature = "fonts")]
+  pub fn reset_complex_text(&mut self) -> Result<&mut Self> {
+    debug!("Disabling complex text");
+    self.1.set_complex_text(None);
+    Ok(self)
+  }
+
+  /// Stores a graphic in non-volatile memory under a key of two printable characters, so it
+  /// can be printed with `nv_graphic` without sending it again
+  #[cfg(feature = "graphics")]
+  pub fn define_nv_graphic<F>(&mut self, key: &str, function: F) -> Result<&mut Self>
+  where
+    F: Fn(&mut GraphicBuilder) -> &mut GraphicBuilder,
+  {
+    let mut builder = Graphic::builder();
+    function(&mut builder);
+    let graphic = builder.build()?;
+    debug!("Defining NV graphic: {}", key);
+    let bytes = self.1.define_nv_graphic(key, graphic)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  pub fn nv_graphic(&mut self, key: &str) -> Result<&mut Self> {
+    debug!("Printing NV graphic: {}", key);
+    let bytes = self.1.nv_graphic(key, &GraphicSize::Normal)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  pub fn delete_nv_graphic(&mut self, key: &str) -> Result<&mut Self> {
+    debug!("Deleting NV graphic: {}", key);
+    let bytes = self.1.delete_nv_graphic(key)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  pub fn delete_nv_graphics(&mut self) -> Result<&mut Self> {
+    debug!("Deleting all NV graphics");
+    let bytes = self.1.delete_nv_graphics();
+    self.0.write(bytes).map(|()| self)
+  }
+
+  /// Lists the keys of the graphics stored in non-volatile memory
+  pub fn nv_graphic_keys(&mut self) -> Result<Vec<String>> {
+    debug!("Requesting NV graphic keys");
+    let bytes = self.1.nv_graphic_keys().to_vec();
+    self.0.write(&bytes)?;
+    self.0.flush()?;
+
+    let mut keys = Vec::new();
+    loop {
+      let (block, more) = Protocol::parse_nv_graphic_keys(&self.read_reply()?)?;
+      keys.extend(block);
+      if !more {
+        return Ok(keys);
+      }
+      let bytes = self.1.nv_graphic_keys_next().to_vec();
+      self.0.write(&bytes)?;
+      self.0.flush()?;
+    }
+  }
+
+  /// The remaining non-volatile memory for graphics, in bytes
+  pub fn nv_graphic_capacity(&mut self) -> Result<u32> {
+    debug!("Requesting NV graphics capacity");
+    let bytes = self.1.nv_graphic_capacity().to_vec();
+    self.0.write(&bytes)?;
+    self.0.flush()?;
+    Protocol::parse_nv_graphic_capacity(&self.read_reply()?)
+  }
+
+  /// Replaces the legacy NV bit images, for printers without NV graphics support
+  #[cfg(feature = "graphics")]
+  pub fn define_nv_bit_images(&mut self, graphics: Vec<Graphic>) -> Result<&mut Self> {
+    debug!("Defining {} NV bit images", graphics.len());
+    let bytes = self.1.define_nv_bit_images(graphics)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  pub fn nv_bit_image(&mut self, number: u8) -> Result<&mut Self> {
+    debug!("Printing NV bit image: {}", number);
+    let bytes = self.1.nv_bit_image(number, &GraphicSize::Normal)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  /// Stores a graphic in the printer's RAM until it is reset or turned off
+  #[cfg(feature = "graphics")]
+  pub fn define_download_bit_image<F>(&mut self, function: F) -> Result<&mut Self>
+  where
+    F: Fn(&mut GraphicBuilder) -> &mut GraphicBuilder,
+  {
+    let mut builder = Graphic::builder();
+    function(&mut builder);
+    let graphic = builder.build()?;
+    debug!("Defining download bit image");
+    let bytes = self.1.define_download_bit_image(graphic)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  pub fn download_bit_image(&mut self) -> Result<&mut Self> {
+    debug!("Printing download bit image");
+    let bytes = self.1.download_bit_image(&GraphicSize::Normal)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
   pub fn reverse_colours(&mut self, enabled: bool) -> Result<&mut Self> {
     debug!("Reverse colours: {}", enabled);
     let bytes = self.1.reverse_colours(enabled)?;
@@ -209,6 +397,76 @@ impl Printer {
     self.0.write(bytes).map(|_| self)
   }
 
+  /// Requests a status from the printer and waits for its reply. Use `Status::check` to turn
+  /// error states into errors.
+  pub fn status(&mut self, kind: StatusKind) -> Result<Status> {
+    self.request_status(&kind)?;
+    self.0.flush()?;
+
+    let mut reply = [0; 1];
+    if self.read(&mut reply, REPLY_TIMEOUT)? == 0 {
+      return Err(PrinterError::Io(format!(
+        "No reply to the {} status request",
+        kind
+      )));
+    }
+    kind.parse(reply[0])
+  }
+
+  /// Writes a status request without waiting for the reply
+  pub(crate) fn request_status(&mut self, kind: &StatusKind) -> Result<&mut Self> {
+    debug!("Requesting {} status", kind);
+    let bytes = self.1.status(kind)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  /// Makes the printer send a message whenever one of the given statuses changes, or stops it
+  /// if there are none. Use `listen` to receive the messages.
+  pub fn asb(&mut self, reports: &[AsbReport]) -> Result<&mut Self> {
+    debug!("Enabling automatic status back for {:?}", reports);
+    let bytes = self.1.asb(reports)?;
+    self.0.write(&bytes).map(|()| self)
+  }
+
+  /// Listens for automatic status back messages on a background thread, calling the callback
+  /// with each change they report. Only drivers that can read from the printer are supported.
+  /// While the listener runs, it reads everything the printer sends and passes the replies to
+  /// other requests, such as `status`, back to the printer.
+  pub fn listen<F>(&mut self, callback: F) -> Result<AsbListener>
+  where
+    F: Fn(PrinterEvent) + Send + 'static,
+  {
+    if self.2.upgrade().is_some() {
+      return Err(PrinterError::configuration(
+        "The printer already has a running listener",
+      ));
+    }
+    debug!("Listening for printer events");
+    let replies = Arc::new(Replies::default());
+    let listener = AsbListener::spawn(Arc::clone(&self.0), Arc::clone(&replies), callback)?;
+    self.2 = Arc::downgrade(&replies);
+    Ok(listener)
+  }
+
+  /// Reads a reply from the printer, waiting at most `timeout` for it
+  pub fn read(&mut self, buffer: &mut [u8], timeout: Duration) -> Result<usize> {
+    match self.2.upgrade() {
+      Some(replies) => replies.read(buffer, timeout),
+      None => self.0.read(buffer, timeout),
+    }
+  }
+
+  /// Reads a reply terminated by NUL, such as the replies to the NV graphics requests
+  fn read_reply(&mut self) -> Result<Vec<u8>> {
+    let mut reply = Vec::new();
+    let mut buffer = [0; 64];
+    while reply.last() != Some(&constants::NIL) {
+      let read = self.read(&mut buffer, REPLY_TIMEOUT)?;
+      reply.extend_from_slice(&buffer[..read]);
+    }
+    Ok(reply)
+  }
+
   pub fn builder() -> PrinterBuilder {
     PrinterBuilder::new()
   }
@@ -217,6 +475,7 @@ impl Printer {
 pub struct PrinterBuilder {
   driver: Box<dyn Driver>,
   encoder: Encoder,
+  capabilities: Capabilities,
 }
 
 impl PrinterBuilder {
@@ -227,6 +486,7 @@ impl PrinterBuilder {
     Self {
       driver: Box::<NoopDriver>::default(),
       encoder: Encoder::default(),
+      capabilities: Capabilities::default(),
     }
   }
 
@@ -240,11 +500,84 @@ impl PrinterBuilder {
     self
   }
 
+  pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+    self.capabilities = capabilities;
+    self
+  }
+
   pub fn build(self) -> Printer {
     let driver = self.driver;
     let encoder = self.encoder;
-    let protocol = Protocol::new(encoder);
+    debug!("Printer capabilities: {}", self.capabilities);
+    let protocol = Protocol::with_capabilities(encoder, self.capabilities);
 
     Printer::new(driver, protocol)
   }
+
+  /// Builds an `AsyncPrinter` that sends its commands through the async driver, instead of
+  /// the driver set on the builder
+  #[cfg(feature = "tokio")]
+  pub fn build_async(self, driver: Box<dyn AsyncDriver>) -> AsyncPrinter {
+    AsyncPrinter::new(driver, self)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::io::MockDriver;
+
+  #[test]
+  fn test_nv_graphic_queries() -> Result<()> {
+    let driver = MockDriver::default();
+    let mut printer = Printer::open(Box::new(driver.clone()))?;
+
+    // the key list arrives in two blocks, split across reads
+    driver.reply([0x37, 0x72, 0x41, b'L', b'1'])?;
+    driver.reply([b'L', b'2', 0x00])?;
+    driver.reply([0x37, 0x72, 0x40, b'A', b'B', 0x00])?;
+    assert_eq!(printer.nv_graphic_keys()?, vec!["L1", "L2", "AB"]);
+    assert_eq!(
+      driver.written()?,
+      [constants::NV_GRAPHICS_KEY_LIST, &[constants::ACK]].concat()
+    );
+
+    driver.reply(b"\x37\x311048576\x00")?;
+    assert_eq!(printer.nv_graphic_capacity()?, 1_048_576);
+
+    assert!(matches!(
+      printer.nv_graphic_capacity(),
+      Err(PrinterError::Timeout(_))
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_status() -> Result<()> {
+    let driver = MockDriver::default();
+    let mut printer = Printer::open(Box::new(driver.clone()))?;
+
+    driver.reply([0x16])?;
+    assert_eq!(
+      printer.status(StatusKind::Paper)?,
+      StatusKind::Paper.parse(0x16)?
+    );
+    driver.reply([0x72])?;
+    assert!(matches!(
+      printer.status(StatusKind::Paper)?.check(),
+      Err(PrinterError::PaperEnd)
+    ));
+    driver.reply([0x12])?;
+    printer.status(StatusKind::Peeler)?;
+    assert_eq!(
+      driver.written()?,
+      vec![0x10, 0x04, 0x04, 0x10, 0x04, 0x04, 0x10, 0x04, 0x08, 0x03]
+    );
+
+    assert!(matches!(
+      printer.status(StatusKind::Printer),
+      Err(PrinterError::Timeout(_))
+    ));
+    Ok(())
+  }
 }